@@ -0,0 +1,270 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::BTreeMap,
+    fmt::Write,
+    hint::black_box,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use common::{ParseError, Solution};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting every allocation so benchmarks can report them.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn allocation_counters() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Stage {
+    pub const ALL: [Self; 3] = [Self::Parse, Self::Part1, Self::Part2];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Part1 => "part1",
+            Self::Part2 => "part2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.name() == name)
+    }
+}
+
+/// Timings of one stage over all runs, plus the allocations made by a single run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+/// Runs `stage` `runs` times; allocations are taken from the first run.
+fn sample<T>(runs: usize, mut stage: impl FnMut() -> T) -> Stats {
+    let mut times = Vec::with_capacity(runs);
+    let mut allocations = None;
+    for _ in 0..runs {
+        let (count_before, bytes_before) = allocation_counters();
+        let start = Instant::now();
+        let output = black_box(stage());
+        times.push(start.elapsed());
+        let (count_after, bytes_after) = allocation_counters();
+        drop(output);
+        allocations.get_or_insert((count_after - count_before, bytes_after - bytes_before));
+    }
+    times.sort();
+    let (allocations, allocated_bytes) = allocations.unwrap_or_default();
+    Stats {
+        runs,
+        min: times[0],
+        median: times[times.len() / 2],
+        max: times[times.len() - 1],
+        allocations,
+        allocated_bytes,
+    }
+}
+
+/// The stats of every stage of one day.
+pub type Measurements = Vec<(Stage, Stats)>;
+
+/// Benchmarks parsing and both parts of `S`, each `runs` times.
+pub fn measure<S: Solution>(input: &str, runs: usize) -> Result<Measurements, ParseError> {
+    assert!(runs > 0, "at least one run is needed");
+    let input = common::normalize(input);
+    let parsed = S::parse(&input)?;
+    let parse = sample(runs, || S::parse(black_box(&input)));
+    let part1 = sample(runs, || S::part1(black_box(&parsed)));
+    let part2 = sample(runs, || S::part2(black_box(&parsed)));
+    Ok(vec![
+        (Stage::Parse, parse),
+        (Stage::Part1, part1),
+        (Stage::Part2, part2),
+    ])
+}
+
+/// Benchmark results keyed by day and stage.
+pub type Report = BTreeMap<(u8, Stage), Stats>;
+
+const REPORT_HEADER: &str =
+    "day\tstage\truns\tmin_ns\tmedian_ns\tmax_ns\tallocations\tallocated_bytes";
+
+/// Serializes a report as tab-separated values with a header row.
+pub fn write_report(report: &Report) -> String {
+    let mut out = format!("{REPORT_HEADER}\n");
+    for ((day, stage), stats) in report {
+        writeln!(
+            out,
+            "{day}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            stage.name(),
+            stats.runs,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.max.as_nanos(),
+            stats.allocations,
+            stats.allocated_bytes
+        )
+        .unwrap();
+    }
+    out
+}
+
+pub fn read_report(contents: &str) -> Result<Report, String> {
+    let mut lines = contents.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header == REPORT_HEADER => {}
+        _ => return Err("not a benchmark report: missing header".to_string()),
+    }
+    lines
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            parse_row(line).ok_or_else(|| format!("malformed benchmark report row {}", i + 1))
+        })
+        .collect()
+}
+
+fn parse_row(line: &str) -> Option<((u8, Stage), Stats)> {
+    let fields = line.split('\t').collect::<Vec<_>>();
+    let [day, stage, runs, min, median, max, allocations, allocated_bytes] = fields[..] else {
+        return None;
+    };
+    let nanos = |field: &str| field.parse().ok().map(Duration::from_nanos);
+    let stats = Stats {
+        runs: runs.parse().ok()?,
+        min: nanos(min)?,
+        median: nanos(median)?,
+        max: nanos(max)?,
+        allocations: allocations.parse().ok()?,
+        allocated_bytes: allocated_bytes.parse().ok()?,
+    };
+    Some(((day.parse().ok()?, Stage::from_name(stage)?), stats))
+}
+
+/// The median of one stage in two reports, and how much it changed in percent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub day: u8,
+    pub stage: Stage,
+    pub before: Duration,
+    pub after: Duration,
+    pub change: f64,
+}
+
+/// How the stages of one report fared in another.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Comparison {
+    pub changes: Vec<Change>,
+    /// The stages of the first report that the second one has no measurements for.
+    pub missing: Vec<(u8, Stage)>,
+}
+
+/// Compares the medians of every stage in `before` with those in `after`, noting the stages that
+/// `after` no longer has.
+pub fn compare(before: &Report, after: &Report) -> Comparison {
+    let mut comparison = Comparison::default();
+    for (&(day, stage), old) in before {
+        let Some(new) = after.get(&(day, stage)) else {
+            comparison.missing.push((day, stage));
+            continue;
+        };
+        let (before, after) = (old.median, new.median);
+        let change = if before.is_zero() {
+            0.0
+        } else {
+            (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0
+        };
+        comparison.changes.push(Change {
+            day,
+            stage,
+            before,
+            after,
+            change,
+        });
+    }
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_ms: u64) -> Stats {
+        Stats {
+            runs: 3,
+            min: Duration::from_millis(median_ms - 1),
+            median: Duration::from_millis(median_ms),
+            max: Duration::from_millis(median_ms + 1),
+            allocations: 4,
+            allocated_bytes: 128,
+        }
+    }
+
+    #[test]
+    fn report_round_trip() {
+        let report = Report::from([((1, Stage::Parse), stats(2)), ((7, Stage::Part2), stats(9))]);
+        assert_eq!(read_report(&write_report(&report)), Ok(report));
+        assert!(read_report("day\tstage\n").is_err());
+        assert!(read_report(&format!("{REPORT_HEADER}\n1\tparse\t3")).is_err());
+    }
+
+    #[test]
+    fn compares_medians() {
+        let before = Report::from([
+            ((1, Stage::Part1), stats(10)),
+            ((2, Stage::Part1), stats(10)),
+        ]);
+        let after = Report::from([((1, Stage::Part1), stats(15))]);
+        let Comparison { changes, missing } = compare(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].day, changes[0].stage), (1, Stage::Part1));
+        assert!((changes[0].change - 50.0).abs() < 1e-9);
+        assert_eq!(missing, [(2, Stage::Part1)]);
+        assert!(compare(&after, &before).missing.is_empty());
+    }
+
+    #[test]
+    fn samples_every_run() {
+        let mut calls = 0;
+        let stats = sample(5, || {
+            calls += 1;
+            vec![0u8; 16]
+        });
+        assert_eq!((calls, stats.runs), (5, 5));
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+        assert!(stats.allocations >= 1 && stats.allocated_bytes >= 16);
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+
+use common::{
+    Generate, Input, ParseError, Record, Rng, Solution, SolveError, Solver, Stream, StreamSolver,
+};
+use visualize::{Simulation, Simulator, Visualize};
+
+use crate::bench::{self, Measurements};
+
+/// The workspace with every day's directory, so the runner finds them from anywhere.
+pub const WORKSPACE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Self; 2] = [Self::One, Self::Two];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "1" => Some(Self::One),
+            "2" => Some(Self::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+pub struct Day {
+    pub number: u8,
+    part1: Solver,
+    part2: Solver,
+    bench: fn(&str, usize) -> Result<Measurements, ParseError>,
+    generate: fn(&mut Rng, usize) -> String,
+    simulate: Option<Simulator>,
+    stream: Option<[StreamSolver; 2]>,
+}
+
+impl Day {
+    const fn new<S: Solution + Generate>(number: u8) -> Self {
+        Self {
+            number,
+            part1: common::try_solve_part1::<S>,
+            part2: common::try_solve_part2::<S>,
+            bench: bench::measure::<S>,
+            generate: S::generate,
+            simulate: None,
+            stream: None,
+        }
+    }
+
+    /// Lets `aoc run` solve the day while reading its input from stdin.
+    const fn streamed<S: Stream>(self) -> Self {
+        Self {
+            stream: Some([common::try_stream_part1::<S>, common::try_stream_part2::<S>]),
+            ..self
+        }
+    }
+
+    /// Lets the day be watched with `aoc visualize`.
+    const fn visualized<S: Visualize>(self) -> Self {
+        Self {
+            simulate: Some(visualize::simulate::<S>),
+            ..self
+        }
+    }
+
+    pub fn solver(&self, part: Part) -> Solver {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<String, SolveError> {
+        self.solver(part)(input)
+    }
+
+    /// Reads `input` and solves `part` with it. With `stream`, input from stdin is solved while it
+    /// is read if the day can; other inputs are always solved whole.
+    pub fn record(&self, part: Part, input: &Input, stream: bool) -> Record {
+        let (day, dir) = (self.number, self.dir());
+        match self.stream {
+            Some([part1, part2]) if stream && *input == Input::Stdin => {
+                let solve = match part {
+                    Part::One => part1,
+                    Part::Two => part2,
+                };
+                common::stream_record(day, part.number(), &dir, input, solve)
+            }
+            _ => common::solve_record(day, part.number(), &dir, input, self.solver(part)),
+        }
+    }
+
+    /// Times parsing and both parts over `runs` runs each.
+    pub fn bench(&self, input: &str, runs: usize) -> Result<Measurements, ParseError> {
+        (self.bench)(input, runs)
+    }
+
+    /// A random input of the given size, the same for the same seed.
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+
+    /// The simulation behind `part` of the day, started from `input`, if the day has one.
+    pub fn simulate(
+        &self,
+        part: Part,
+        input: &str,
+    ) -> Option<Result<Box<dyn Simulation>, ParseError>> {
+        self.simulate.map(|simulate| simulate(input, part.number()))
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        Path::new(WORKSPACE).join(format!("day-{:02}", self.number))
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        self.dir().join("input.txt")
+    }
+
+    /// The known-good answers for [`Day::input_path`], checked by `aoc verify`.
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir().join("answers.txt")
+    }
+}
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+pub const DAYS: &[Day] = &[
+    Day::new::<day_01::Day01>(1).streamed::<day_01::Day01>(),
+    Day::new::<day_02::Day02>(2).streamed::<day_02::Day02>(),
+    Day::new::<day_03::Day03>(3).streamed::<day_03::Day03>(),
+    Day::new::<day_04::Day04>(4),
+    Day::new::<day_05::Day05>(5),
+    Day::new::<day_06::Day06>(6).visualized::<day_06::Day06>(),
+    Day::new::<day_07::Day07>(7).streamed::<day_07::Day07>(),
+    Day::new::<day_08::Day08>(8),
+    Day::new::<day_09::Day09>(9),
+    Day::new::<day_10::Day10>(10),
+    Day::new::<day_11::Day11>(11),
+    Day::new::<day_12::Day12>(12),
+    Day::new::<day_13::Day13>(13).streamed::<day_13::Day13>(),
+    Day::new::<day_14::Day14>(14).visualized::<day_14::Day14>(),
+    Day::new::<day_15::Day15>(15).visualized::<day_15::Day15>(),
+];
@@ -0,0 +1,574 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+mod bench;
+mod days;
+mod new_day;
+mod verify;
+
+use bench::{CountingAllocator, Report};
+use common::{Format, Input};
+use days::{Day, Part, DAYS};
+use verify::Outcome;
+use visualize::{Export, ImageFormat};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "\
+Usage:
+    aoc run --day <N> [--part <1|2>] [--input <PATH|-> | --example <N>] [--format <text|json>]
+    aoc run --all [--format <text|json>]
+    aoc bench [--day <N>] [--runs <N>] [--output <PATH>]
+    aoc compare <BASELINE> <CURRENT> [--threshold <PERCENT>]
+    aoc verify [--day <N>]
+    aoc generate --day <N> [--seed <N>] [--size <N>] [--output <PATH>]
+    aoc visualize --day <N> [--part <1|2>] [--input <PATH|-> | --example <N>] [--skip <N>]
+                  [--fps <N>] [--export <ppm|png|gif> --output <PATH> [--scale <N>] [--frames <N>]]
+    aoc new-day <N>";
+
+const DEFAULT_RUNS: usize = 10;
+const DEFAULT_THRESHOLD: f64 = 10.0;
+const DEFAULT_SIZE: usize = 100;
+const DEFAULT_FPS: f64 = 10.0;
+
+#[derive(Debug)]
+enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Compare(CompareArgs),
+    Verify(Option<u8>),
+    Generate(GenerateArgs),
+    Visualize(VisualizeArgs),
+    NewDay(u8),
+}
+
+#[derive(Debug, Default)]
+struct RunArgs {
+    day: Option<u8>,
+    all: bool,
+    part: Option<Part>,
+    input: Input,
+    format: Format,
+}
+
+#[derive(Debug)]
+struct BenchArgs {
+    day: Option<u8>,
+    runs: usize,
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct CompareArgs {
+    baseline: PathBuf,
+    current: PathBuf,
+    threshold: f64,
+}
+
+#[derive(Debug)]
+struct GenerateArgs {
+    day: u8,
+    seed: u64,
+    size: usize,
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct VisualizeArgs {
+    day: u8,
+    part: Part,
+    input: Input,
+    /// Steps taken before the first frame is shown.
+    skip: usize,
+    fps: f64,
+    /// Writes frames instead of playing them on the terminal.
+    export: Option<(Export, PathBuf)>,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    common::report_to_terminal();
+    let result = parse_args(&args).and_then(|command| match command {
+        Command::Run(args) => run(args),
+        Command::Bench(args) => bench(args),
+        Command::Compare(args) => compare(args),
+        Command::Verify(day) => verify(day),
+        Command::Generate(args) => generate(args),
+        Command::Visualize(args) => visualize(args),
+        Command::NewDay(number) => new_day(number),
+    });
+    if let Err(message) = result {
+        eprintln!("error: {message}\n\n{USAGE}");
+        process::exit(2);
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = args.split_first().ok_or("missing command")?;
+    match command.as_str() {
+        "run" => parse_run_args(rest).map(Command::Run),
+        "bench" => parse_bench_args(rest).map(Command::Bench),
+        "compare" => parse_compare_args(rest).map(Command::Compare),
+        "verify" => parse_verify_args(rest).map(Command::Verify),
+        "generate" => parse_generate_args(rest).map(Command::Generate),
+        "visualize" => parse_visualize_args(rest).map(Command::Visualize),
+        "new-day" => match rest {
+            [number] => parse_day(number).map(Command::NewDay),
+            _ => Err("new-day needs exactly one day number".to_string()),
+        },
+        other => Err(format!("unknown command `{other}`")),
+    }
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    value
+        .parse::<u8>()
+        .map_err(|_| format!("invalid day `{value}`"))
+}
+
+fn parse_run_args(args: &[String]) -> Result<RunArgs, String> {
+    let mut run_args = RunArgs::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => run_args.all = true,
+            "--day" => {
+                let value = args.next().ok_or("--day needs a value")?;
+                run_args.day = Some(parse_day(value)?);
+            }
+            "--part" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                let part = Part::parse(value).ok_or(format!("invalid part `{value}`"))?;
+                run_args.part = Some(part);
+            }
+            "--format" => {
+                let value = args.next().ok_or("--format needs a value")?;
+                run_args.format = Format::parse(value)?;
+            }
+            "--input" | "--example" if run_args.input != Input::Puzzle => {
+                return Err("--input and --example are mutually exclusive".to_string());
+            }
+            "--input" => {
+                let value = args.next().ok_or("--input needs a value")?;
+                run_args.input = Input::from_path(value);
+            }
+            "--example" => {
+                let value = args.next().ok_or("--example needs a value")?;
+                run_args.input = Input::parse_example(value)?;
+            }
+            other => return Err(format!("unexpected argument `{other}`")),
+        }
+    }
+    match (run_args.all, run_args.day) {
+        (true, Some(_)) => Err("--all and --day are mutually exclusive".to_string()),
+        (false, None) => Err("either --day or --all is required".to_string()),
+        (true, None) if run_args.input != Input::Puzzle => {
+            Err("--input and --example can only be used with a single --day".to_string())
+        }
+        _ => Ok(run_args),
+    }
+}
+
+fn parse_bench_args(args: &[String]) -> Result<BenchArgs, String> {
+    let mut bench_args = BenchArgs {
+        day: None,
+        runs: DEFAULT_RUNS,
+        output: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => {
+                let value = args.next().ok_or("--day needs a value")?;
+                bench_args.day = Some(parse_day(value)?);
+            }
+            "--runs" => {
+                let value = args.next().ok_or("--runs needs a value")?;
+                bench_args.runs = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&runs| runs > 0)
+                    .ok_or(format!("invalid number of runs `{value}`"))?;
+            }
+            "--output" => {
+                let value = args.next().ok_or("--output needs a value")?;
+                bench_args.output = Some(PathBuf::from(value));
+            }
+            other => return Err(format!("unexpected argument `{other}`")),
+        }
+    }
+    Ok(bench_args)
+}
+
+fn parse_compare_args(args: &[String]) -> Result<CompareArgs, String> {
+    let mut paths = Vec::new();
+    let mut threshold = DEFAULT_THRESHOLD;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threshold" => {
+                let value = args.next().ok_or("--threshold needs a value")?;
+                threshold = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|threshold| *threshold >= 0.0)
+                    .ok_or(format!("invalid threshold `{value}`"))?;
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("unexpected argument `{other}`"))
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    match <[PathBuf; 2]>::try_from(paths) {
+        Ok([baseline, current]) => Ok(CompareArgs {
+            baseline,
+            current,
+            threshold,
+        }),
+        Err(_) => Err("compare needs a baseline and a current report".to_string()),
+    }
+}
+
+fn parse_verify_args(args: &[String]) -> Result<Option<u8>, String> {
+    match args {
+        [] => Ok(None),
+        [flag, value] if flag == "--day" => parse_day(value).map(Some),
+        [flag] if flag == "--day" => Err("--day needs a value".to_string()),
+        [other, ..] => Err(format!("unexpected argument `{other}`")),
+    }
+}
+
+fn parse_generate_args(args: &[String]) -> Result<GenerateArgs, String> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = DEFAULT_SIZE;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => {
+                let value = args.next().ok_or("--day needs a value")?;
+                day = Some(parse_day(value)?);
+            }
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                seed = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid seed `{value}`"))?;
+            }
+            "--size" => {
+                let value = args.next().ok_or("--size needs a value")?;
+                size = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&size| size > 0)
+                    .ok_or(format!("invalid size `{value}`"))?;
+            }
+            "--output" => {
+                let value = args.next().ok_or("--output needs a value")?;
+                output = Some(PathBuf::from(value));
+            }
+            other => return Err(format!("unexpected argument `{other}`")),
+        }
+    }
+    Ok(GenerateArgs {
+        day: day.ok_or("generate needs a --day")?,
+        seed,
+        size,
+        output,
+    })
+}
+
+fn parse_visualize_args(args: &[String]) -> Result<VisualizeArgs, String> {
+    let mut day = None;
+    let mut part = Part::One;
+    let mut input = Input::Puzzle;
+    let mut skip = 0;
+    let mut fps = DEFAULT_FPS;
+    let mut format = None;
+    let mut output = None;
+    let mut scale = None;
+    let mut frames = None;
+    let positive = |flag: &str, value: &str| {
+        value
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or(format!("invalid {flag} `{value}`"))
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => {
+                let value = args.next().ok_or("--day needs a value")?;
+                day = Some(parse_day(value)?);
+            }
+            "--part" => {
+                let value = args.next().ok_or("--part needs a value")?;
+                part = Part::parse(value).ok_or(format!("invalid part `{value}`"))?;
+            }
+            "--input" | "--example" if input != Input::Puzzle => {
+                return Err("--input and --example are mutually exclusive".to_string());
+            }
+            "--input" => {
+                let value = args.next().ok_or("--input needs a value")?;
+                input = Input::from_path(value);
+            }
+            "--example" => {
+                let value = args.next().ok_or("--example needs a value")?;
+                input = Input::parse_example(value)?;
+            }
+            "--skip" => {
+                let value = args.next().ok_or("--skip needs a value")?;
+                skip = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid skip `{value}`"))?;
+            }
+            "--fps" => {
+                let value = args.next().ok_or("--fps needs a value")?;
+                fps = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|&fps| fps > 0.0 && fps.is_finite())
+                    .ok_or(format!("invalid fps `{value}`"))?;
+            }
+            "--export" => {
+                let value = args.next().ok_or("--export needs a value")?;
+                format = Some(ImageFormat::parse(value)?);
+            }
+            "--output" => {
+                let value = args.next().ok_or("--output needs a value")?;
+                output = Some(PathBuf::from(value));
+            }
+            "--scale" => {
+                let value = args.next().ok_or("--scale needs a value")?;
+                scale = Some(positive("scale", value)?);
+            }
+            "--frames" => {
+                let value = args.next().ok_or("--frames needs a value")?;
+                frames = Some(positive("frames", value)?);
+            }
+            other => return Err(format!("unexpected argument `{other}`")),
+        }
+    }
+    let export = match (format, output) {
+        (Some(format), Some(output)) => {
+            let mut export = Export::new(format);
+            export.fps = fps;
+            export.scale = scale.unwrap_or(export.scale);
+            export.frames = frames.unwrap_or(export.frames);
+            Some((export, output))
+        }
+        (None, None) if scale.is_none() && frames.is_none() => None,
+        (None, None) => return Err("--scale and --frames need an --export".to_string()),
+        (Some(_), None) => return Err("--export needs an --output".to_string()),
+        (None, Some(_)) => return Err("--output needs an --export".to_string()),
+    };
+    Ok(VisualizeArgs {
+        day: day.ok_or("visualize needs a --day")?,
+        part,
+        input,
+        skip,
+        fps,
+        export,
+    })
+}
+
+fn selected_days(day: Option<u8>) -> Result<Vec<&'static Day>, String> {
+    match day {
+        Some(number) => Ok(vec![
+            days::find(number).ok_or(format!("day {number} is not solved"))?
+        ]),
+        None => Ok(DAYS.iter().collect()),
+    }
+}
+
+fn read_input(path: &PathBuf) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    // stdin can only be streamed once, so solving both parts from it reads it whole
+    let stream = parts.len() == 1;
+    let mut failures = 0;
+    for day in selected_days(args.day)? {
+        for &part in &parts {
+            let record = day.record(part, &args.input, stream);
+            failures += usize::from(record.answer.is_err());
+            match (args.format, &record.answer) {
+                (Format::Json, _) => println!("{}", record.to_json()),
+                (Format::Text, Ok(answer)) => println!(
+                    "Day {:02} part {}: {answer} ({:.2?})",
+                    day.number,
+                    part.number(),
+                    record.elapsed.unwrap_or_default()
+                ),
+                (Format::Text, Err(e)) => {
+                    println!("Day {:02} part {}: error: {e}", day.number, part.number())
+                }
+            }
+        }
+    }
+    if failures > 0 {
+        // on stderr, so the JSON records stay one per line
+        eprintln!("{failures} part(s) failed");
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    let mut report = Report::new();
+    for day in selected_days(args.day)? {
+        let path = day.input_path();
+        let input = read_input(&path)?;
+        let stages = day.bench(&input, args.runs).map_err(|e| {
+            format!(
+                "cannot benchmark day {}: error in {}: {e}",
+                day.number,
+                path.display()
+            )
+        })?;
+        for (stage, stats) in stages {
+            println!(
+                "Day {:02} {:<5}: min {:>10.2?}  median {:>10.2?}  max {:>10.2?}  {} allocations ({} bytes)",
+                day.number,
+                stage.name(),
+                stats.min,
+                stats.median,
+                stats.max,
+                stats.allocations,
+                stats.allocated_bytes
+            );
+            report.insert((day.number, stage), stats);
+        }
+    }
+    if let Some(path) = args.output {
+        fs::write(&path, bench::write_report(&report))
+            .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn compare(args: CompareArgs) -> Result<(), String> {
+    let read = |path: &PathBuf| {
+        read_input(path).and_then(|contents| {
+            bench::read_report(&contents).map_err(|e| format!("{}: {e}", path.display()))
+        })
+    };
+    let (baseline, current) = (read(&args.baseline)?, read(&args.current)?);
+    let comparison = bench::compare(&baseline, &current);
+    let mut regressions = 0;
+    for change in comparison.changes {
+        let regressed = change.change > args.threshold;
+        regressions += usize::from(regressed);
+        println!(
+            "Day {:02} {:<5}: {:>10.2?} -> {:>10.2?} ({:+.1}%){}",
+            change.day,
+            change.stage.name(),
+            change.before,
+            change.after,
+            change.change,
+            if regressed { "  REGRESSION" } else { "" }
+        );
+    }
+    for &(day, stage) in &comparison.missing {
+        println!(
+            "Day {day:02} {:<5}: MISSING from the current report",
+            stage.name()
+        );
+    }
+    if regressions > 0 {
+        println!(
+            "{regressions} stage(s) regressed by more than {}%",
+            args.threshold
+        );
+    }
+    if !comparison.missing.is_empty() {
+        println!(
+            "{} stage(s) of the baseline were not measured",
+            comparison.missing.len()
+        );
+    }
+    if regressions > 0 || !comparison.missing.is_empty() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn verify(day: Option<u8>) -> Result<(), String> {
+    let mut failures = 0;
+    for day in selected_days(day)? {
+        for (part, outcome) in verify::verify(day)? {
+            failures += usize::from(outcome.is_failure());
+            let status = match outcome {
+                Outcome::Correct(answer) => format!("ok ({answer})"),
+                Outcome::Mismatch { expected, actual } => {
+                    format!("MISMATCH: expected {expected}, got {actual}")
+                }
+                Outcome::Failed(e) => format!("FAILED: {e}"),
+                Outcome::Skipped(reason) => format!("skipped ({reason})"),
+            };
+            println!("Day {:02} part {}: {status}", day.number, part.number());
+        }
+    }
+    if failures > 0 {
+        println!("{failures} answer(s) did not match");
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn generate(args: GenerateArgs) -> Result<(), String> {
+    let day = days::find(args.day).ok_or(format!("day {} is not solved", args.day))?;
+    let input = day.generate(args.seed, args.size);
+    match args.output {
+        Some(path) => {
+            fs::write(&path, input).map_err(|e| format!("cannot write {}: {e}", path.display()))
+        }
+        None => {
+            print!("{input}");
+            Ok(())
+        }
+    }
+}
+
+fn visualize(args: VisualizeArgs) -> Result<(), String> {
+    let day = days::find(args.day).ok_or(format!("day {} is not solved", args.day))?;
+    let part = args.part.number();
+    let name = args.input.describe(&day.dir(), part);
+    let input = args.input.read(&day.dir(), part)?;
+    let mut simulation = day
+        .simulate(args.part, &input)
+        .ok_or(format!("day {} has no visualization", day.number))?
+        .map_err(|e| format!("{name}: {e}"))?;
+    visualize::skip(simulation.as_mut(), args.skip);
+    match args.export {
+        Some((export, path)) => {
+            let frames = visualize::export(simulation.as_mut(), &path, &export)
+                .map_err(|e| format!("cannot export to {}: {e}", path.display()))?;
+            println!("Wrote {frames} frame(s) to {}", path.display());
+            Ok(())
+        }
+        None => visualize::play(simulation.as_mut(), args.fps, &mut io::stdout().lock())
+            .map_err(|e| format!("cannot play the visualization: {e}")),
+    }
+}
+
+fn new_day(number: u8) -> Result<(), String> {
+    let dir = new_day::new_day(Path::new(days::WORKSPACE), number)?;
+    println!(
+        "Created {} and registered it with the workspace and the runner",
+        dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = "day-00";
+
+/// Files of the workspace that list every day, with the line prefix each entry starts with.
+const REGISTRIES: [(&str, &str); 3] = [
+    ("Cargo.toml", "\"day-"),
+    ("aoc/Cargo.toml", "day-"),
+    ("aoc/src/days.rs", "Day::new::<day_"),
+];
+
+fn registry_line(registry: &str, number: u8) -> String {
+    match registry {
+        "Cargo.toml" => format!("    \"day-{number:02}\","),
+        "aoc/Cargo.toml" => format!("day-{number:02} = {{ path = \"../day-{number:02}\" }}"),
+        _ => format!("    Day::new::<day_{number:02}::Day{number:02}>({number}),"),
+    }
+}
+
+/// Replaces every spelling of the template's day number with `number`.
+fn instantiate(template: &str, number: u8) -> String {
+    template
+        .replace("day-00", &format!("day-{number:02}"))
+        .replace("day_00", &format!("day_{number:02}"))
+        .replace("Day00", &format!("Day{number:02}"))
+}
+
+/// Inserts `line` among the lines starting with `prefix` followed by a day number, keeping them
+/// ordered by day.
+fn insert_day_line(contents: &str, prefix: &str, number: u8, line: &str) -> Result<String, String> {
+    let day_of =
+        |line: &str| -> Option<u8> { line.trim().strip_prefix(prefix)?.get(..2)?.parse().ok() };
+    let mut lines = contents.lines().collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| day_of(line).map(|day| (i, day)))
+        .collect::<Vec<_>>();
+    if days.iter().any(|&(_, day)| day == number) {
+        return Err(format!("day {number} is already registered"));
+    }
+    let position = match days.iter().rev().find(|&&(_, day)| day < number) {
+        Some(&(i, _)) => i + 1,
+        None => days.first().ok_or("no days are registered")?.0,
+    };
+    lines.insert(position, line);
+    let mut updated = lines.join("\n");
+    if contents.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+fn template_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("cannot read {}: {e}", dir.display()))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                files.extend(template_files(&path)?);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Creates `day-NN` from the `day-00` template below `root` and registers it with the workspace
+/// and the runner. Nothing is written unless every step can be prepared.
+pub fn new_day(root: &Path, number: u8) -> Result<PathBuf, String> {
+    if !(1..=25).contains(&number) {
+        return Err(format!("day {number} is not between 1 and 25"));
+    }
+    let dir = root.join(format!("day-{number:02}"));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    let template = root.join(TEMPLATE);
+    let files = template_files(&template)?
+        .into_iter()
+        .map(|path| {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            let relative = path.strip_prefix(&template).unwrap().to_path_buf();
+            Ok((dir.join(relative), instantiate(&contents, number)))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let registries = REGISTRIES
+        .into_iter()
+        .map(|(registry, prefix)| {
+            let path = root.join(registry);
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            let line = registry_line(registry, number);
+            let updated = insert_day_line(&contents, prefix, number, &line)
+                .map_err(|e| format!("{registry}: {e}"))?;
+            Ok((path, updated))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    for (path, contents) in files.into_iter().chain(registries) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("cannot create {}: {e}", parent.display()))?;
+        }
+        fs::write(&path, contents).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_in_day_order() {
+        let members = "members = [\n    \"aoc\",\n    \"day-01\",\n    \"day-03\",\n]\n";
+        let updated = insert_day_line(members, "\"day-", 2, &registry_line("Cargo.toml", 2));
+        assert_eq!(
+            updated.unwrap(),
+            "members = [\n    \"aoc\",\n    \"day-01\",\n    \"day-02\",\n    \"day-03\",\n]\n"
+        );
+        let updated = insert_day_line(members, "\"day-", 9, "    \"day-09\",").unwrap();
+        assert!(updated.ends_with("\"day-03\",\n    \"day-09\",\n]\n"));
+        assert!(insert_day_line(members, "\"day-", 3, "").is_err());
+        assert!(insert_day_line("[dependencies]\n", "day-", 3, "").is_err());
+    }
+
+    #[test]
+    fn instantiates_every_spelling() {
+        let template = "use day_00::Day00;\nname = \"day-00-part-1\"";
+        assert_eq!(
+            instantiate(template, 7),
+            "use day_07::Day07;\nname = \"day-07-part-1\""
+        );
+        assert_eq!(
+            registry_line("aoc/src/days.rs", 16),
+            "    Day::new::<day_16::Day16>(16),"
+        );
+    }
+}
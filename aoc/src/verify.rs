@@ -0,0 +1,92 @@
+use std::{fs, io, path::Path};
+
+use common::{Answers, SolveError};
+
+use crate::days::{Day, Part};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct(String),
+    Mismatch { expected: String, actual: String },
+    Failed(SolveError),
+    Skipped(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Mismatch { .. } | Self::Failed(_))
+    }
+}
+
+/// Reads a file, treating a missing file as `None`.
+fn read_optional(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("cannot read {}: {e}", path.display())),
+    }
+}
+
+/// Solves both parts of `day` on its real input and checks them against its answers.
+pub fn verify(day: &Day) -> Result<Vec<(Part, Outcome)>, String> {
+    let skip_all = |reason: String| {
+        Ok(Part::ALL
+            .into_iter()
+            .map(|part| (part, Outcome::Skipped(reason.clone())))
+            .collect())
+    };
+    let Some(input) = read_optional(&day.input_path())? else {
+        return skip_all(format!("no {}", day.input_path().display()));
+    };
+    let Some(answers) = read_optional(&day.answers_path())? else {
+        return skip_all(format!("no {}", day.answers_path().display()));
+    };
+    let answers =
+        Answers::parse(&answers).map_err(|e| format!("{}: {e}", day.answers_path().display()))?;
+    Ok(Part::ALL
+        .into_iter()
+        .map(|part| {
+            let expected = match part {
+                Part::One => answers.part1.as_deref(),
+                Part::Two => answers.part2.as_deref(),
+            };
+            let Some(expected) = expected else {
+                return (part, Outcome::Skipped("no known answer".to_string()));
+            };
+            let outcome = match day.solve(part, &input) {
+                Ok(actual) if actual == expected => Outcome::Correct(actual),
+                Ok(actual) => Outcome::Mismatch {
+                    expected: expected.to_string(),
+                    actual,
+                },
+                Err(e) => Outcome::Failed(e),
+            };
+            (part, outcome)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::DAYS;
+
+    #[test]
+    #[cfg_attr(
+        debug_assertions,
+        ignore = "too slow without optimizations, run with `cargo test --release`"
+    )]
+    fn real_inputs_match_known_answers() {
+        let failures = DAYS
+            .iter()
+            .flat_map(|day| {
+                let outcomes = verify(day).unwrap();
+                outcomes
+                    .into_iter()
+                    .map(move |(part, outcome)| (day.number, part, outcome))
+            })
+            .filter(|(_, _, outcome)| outcome.is_failure())
+            .collect::<Vec<_>>();
+        assert!(failures.is_empty(), "{failures:#?}");
+    }
+}
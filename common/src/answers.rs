@@ -0,0 +1,44 @@
+/// Known-good answers in the `part1: <answer>` / `part2: <answer>` format shared by a day's
+/// `answers.txt` and its example `.expected` sidecars; either part may be left out.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut answers = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected `part<N>: <answer>`", i + 1))?;
+            let slot = match key.trim() {
+                "part1" => &mut answers.part1,
+                "part2" => &mut answers.part2,
+                other => return Err(format!("line {}: unknown part `{other}`", i + 1)),
+            };
+            *slot = Some(value.trim().to_string());
+        }
+        Ok(answers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers = Answers::parse("part1: 42\n\npart2:  abc \n").unwrap();
+        assert_eq!(answers.part1.as_deref(), Some("42"));
+        assert_eq!(answers.part2.as_deref(), Some("abc"));
+        assert_eq!(Answers::parse("part2: 7").unwrap().part1, None);
+        assert_eq!(Answers::parse("part1:").unwrap().part1.as_deref(), Some(""));
+        assert!(Answers::parse("part3: 1").is_err());
+        assert!(Answers::parse("42").is_err());
+    }
+}
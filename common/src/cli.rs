@@ -0,0 +1,370 @@
+use std::{
+    env, fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Instant,
+};
+
+use crate::{
+    input_hash, output::InputHasher, report_to_terminal, Format, Record, Solver, StreamSolver,
+};
+
+/// Where a puzzle input is read from, as chosen on the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Input {
+    /// The day's own `input.txt`.
+    #[default]
+    Puzzle,
+    File(PathBuf),
+    Stdin,
+    /// The day's `partX-exampleN.txt`, falling back to `part1-exampleN.txt`.
+    Example(u32),
+}
+
+impl Input {
+    /// Interprets a path argument, where `-` stands for stdin.
+    pub fn from_path(path: &str) -> Self {
+        match path {
+            "-" => Self::Stdin,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn parse_example(number: &str) -> Result<Self, String> {
+        number
+            .parse()
+            .map(Self::Example)
+            .map_err(|_| format!("invalid example number `{number}`"))
+    }
+
+    /// Parses the arguments of a part binary: nothing, a path, `-` or `--example <N>`.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        match args {
+            [] => Ok(Self::Puzzle),
+            [flag, number] if flag == "--example" => Self::parse_example(number),
+            [flag] if flag == "--example" => Err("--example needs a number".to_string()),
+            [arg] if arg.starts_with("--") => Err(format!("unexpected argument `{arg}`")),
+            [path] => Ok(Self::from_path(path)),
+            [_, extra, ..] => Err(format!("unexpected argument `{extra}`")),
+        }
+    }
+
+    /// The file this input is read from for `part` of the day in `day_dir`, if any.
+    pub fn path(&self, day_dir: &Path, part: u8) -> Result<Option<PathBuf>, String> {
+        match self {
+            Self::Puzzle => Ok(Some(day_dir.join("input.txt"))),
+            Self::File(path) => Ok(Some(path.clone())),
+            Self::Stdin => Ok(None),
+            Self::Example(number) => {
+                let candidates =
+                    [part, 1].map(|part| day_dir.join(format!("part{part}-example{number}.txt")));
+                candidates
+                    .iter()
+                    .find(|path| path.is_file())
+                    .cloned()
+                    .map(Some)
+                    .ok_or_else(|| {
+                        format!(
+                            "there is no example {number} in {}: {} does not exist",
+                            day_dir.display(),
+                            candidates[0].display()
+                        )
+                    })
+            }
+        }
+    }
+
+    /// Reads the input for `part`; stdin is only consumed once, so both parts see the same data.
+    pub fn read(&self, day_dir: &Path, part: u8) -> Result<String, String> {
+        match self.path(day_dir, part)? {
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display())),
+            None => read_stdin(),
+        }
+    }
+
+    /// Opens the input for `part` to be read as it is solved. Stdin is read as it arrives and so
+    /// can only be opened once; use [`Input::read`] when more than one part needs it.
+    pub fn open(&self, day_dir: &Path, part: u8) -> Result<Box<dyn BufRead>, String> {
+        match self.path(day_dir, part)? {
+            Some(path) => match File::open(&path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(e) => Err(format!("cannot read {}: {e}", path.display())),
+            },
+            None => match STDIN.get() {
+                Some(input) => Ok(Box::new(io::Cursor::new(input.clone()?))),
+                None => Ok(Box::new(io::stdin().lock())),
+            },
+        }
+    }
+
+    /// A name for the input in messages.
+    pub fn describe(&self, day_dir: &Path, part: u8) -> String {
+        match self.path(day_dir, part) {
+            Ok(Some(path)) => path.display().to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Puzzle => write!(f, "input.txt"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "stdin"),
+            Self::Example(number) => write!(f, "example {number}"),
+        }
+    }
+}
+
+/// Stdin as read whole by [`Input::read`].
+static STDIN: OnceLock<Result<String, String>> = OnceLock::new();
+
+fn read_stdin() -> Result<String, String> {
+    STDIN
+        .get_or_init(|| {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(|e| format!("cannot read stdin: {e}"))
+        })
+        .clone()
+}
+
+/// Reads `input` and solves `part` with it, timing only the solving.
+pub fn solve_record(day: u8, part: u8, day_dir: &Path, input: &Input, solve: Solver) -> Record {
+    let name = input.describe(day_dir, part);
+    match input.read(day_dir, part) {
+        Ok(contents) => {
+            let start = Instant::now();
+            let answer = solve(&contents).map_err(|e| format!("{name}: {e}"));
+            Record {
+                day,
+                part,
+                input: name,
+                input_hash: Some(input_hash(&contents)),
+                elapsed: Some(start.elapsed()),
+                answer,
+            }
+        }
+        Err(e) => Record {
+            day,
+            part,
+            input: name,
+            input_hash: None,
+            elapsed: None,
+            answer: Err(e),
+        },
+    }
+}
+
+/// Solves `part` while reading `input`, like [`solve_record`] but without holding the whole input
+/// in memory. The elapsed time includes reading.
+pub fn stream_record(
+    day: u8,
+    part: u8,
+    day_dir: &Path,
+    input: &Input,
+    solve: StreamSolver,
+) -> Record {
+    let name = input.describe(day_dir, part);
+    let mut reader = match input.open(day_dir, part) {
+        Ok(reader) => Hashing {
+            inner: reader,
+            hasher: InputHasher::default(),
+        },
+        Err(e) => {
+            return Record {
+                day,
+                part,
+                input: name,
+                input_hash: None,
+                elapsed: None,
+                answer: Err(e),
+            }
+        }
+    };
+    let start = Instant::now();
+    let answer = solve(&mut reader).map_err(|e| format!("{name}: {e}"));
+    let elapsed = start.elapsed();
+    // an error can stop the solver early, so hash whatever it left unread
+    let input_hash = io::copy(&mut reader, &mut io::sink())
+        .ok()
+        .map(|_| reader.hasher.finish());
+    Record {
+        day,
+        part,
+        input: name,
+        input_hash,
+        elapsed: Some(elapsed),
+        answer,
+    }
+}
+
+/// Hashes everything read through it, like [`input_hash`] does with the whole input.
+struct Hashing {
+    inner: Box<dyn BufRead>,
+    hasher: InputHasher,
+}
+
+impl Read for Hashing {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl BufRead for Hashing {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // the buffer is still filled from the last call, so this does not read
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.hasher.update(&buf[..amount]);
+        }
+        self.inner.consume(amount);
+    }
+}
+
+/// Splits `--format <FORMAT>` off the arguments of a part binary and parses the rest as an input.
+fn parse_part_args(args: &[String]) -> Result<(Format, Input), String> {
+    let mut format = Format::Text;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            format = Format::parse(args.next().ok_or("--format needs a value")?)?;
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    Ok((format, Input::from_args(&rest)?))
+}
+
+/// The `main` of a part binary: reads the input chosen on the command line and prints the answer.
+/// `day_dir` is the day's crate directory, so the binary works from anywhere.
+pub fn run_part(part: u8, day_dir: &str, solve: Solver) {
+    run(part, day_dir, |day, day_dir, input| {
+        solve_record(day, part, day_dir, input, solve)
+    });
+}
+
+/// The `main` of a part binary of a day that can also solve its input while reading it, see
+/// [`run_part`]. Input from stdin is streamed through `stream`; files are solved whole with
+/// `solve`, which can spread its work over threads and report progress.
+pub fn run_streamed_part(part: u8, day_dir: &str, solve: Solver, stream: StreamSolver) {
+    run(part, day_dir, |day, day_dir, input| match input {
+        Input::Stdin => stream_record(day, part, day_dir, input, stream),
+        _ => solve_record(day, part, day_dir, input, solve),
+    });
+}
+
+fn run(part: u8, day_dir: &str, solve: impl FnOnce(u8, &Path, &Input) -> Record) {
+    let day_dir = Path::new(day_dir);
+    let name = day_dir.file_name().unwrap_or_default().to_string_lossy();
+    let day = name
+        .strip_prefix("day-")
+        .and_then(|n| n.parse().ok())
+        .unwrap_or_default();
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (format, input) = parse_part_args(&args).unwrap_or_else(|e| {
+        eprintln!(
+            "error: {e}\n\nUsage: {name}-part-{part} [<PATH> | - | --example <N>] [--format <text|json>]"
+        );
+        process::exit(2);
+    });
+    report_to_terminal();
+    let record = solve(day, day_dir, &input);
+    match (format, &record.answer) {
+        (Format::Json, _) => println!("{}", record.to_json()),
+        (Format::Text, Ok(answer)) => println!("{answer}"),
+        (Format::Text, Err(e)) => eprintln!("error: {e}"),
+    }
+    if record.answer.is_err() {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert_eq!(Input::from_args(&[]), Ok(Input::Puzzle));
+        assert_eq!(Input::from_args(&args(&["-"])), Ok(Input::Stdin));
+        assert_eq!(
+            Input::from_args(&args(&["in.txt"])),
+            Ok(Input::File(PathBuf::from("in.txt")))
+        );
+        assert_eq!(
+            Input::from_args(&args(&["--example", "2"])),
+            Ok(Input::Example(2))
+        );
+        assert!(Input::from_args(&args(&["--example", "x"])).is_err());
+        assert!(Input::from_args(&args(&["--example"])).is_err());
+        assert!(Input::from_args(&args(&["a", "b"])).is_err());
+        assert_eq!(
+            parse_part_args(&args(&["--format", "json", "-"])),
+            Ok((Format::Json, Input::Stdin))
+        );
+        assert!(parse_part_args(&args(&["--format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn resolves_examples() {
+        let day_dir = env::temp_dir().join(format!("common-cli-{}", process::id()));
+        fs::create_dir_all(&day_dir).unwrap();
+        for name in ["part1-example1.txt", "part2-example2.txt"] {
+            fs::write(day_dir.join(name), name).unwrap();
+        }
+        let read = |input: Input, part| input.read(&day_dir, part);
+        assert_eq!(read(Input::Example(1), 1).unwrap(), "part1-example1.txt");
+        assert_eq!(read(Input::Example(1), 2).unwrap(), "part1-example1.txt");
+        assert_eq!(read(Input::Example(2), 2).unwrap(), "part2-example2.txt");
+        let error = read(Input::Example(3), 1).unwrap_err();
+        assert!(error.contains("part1-example3.txt"), "{error}");
+        let error = read(Input::Puzzle, 1).unwrap_err();
+        assert!(
+            error.starts_with("cannot read") && error.contains("input.txt"),
+            "{error}"
+        );
+        fs::remove_dir_all(&day_dir).unwrap();
+    }
+
+    #[test]
+    fn streams_records() {
+        let day_dir = env::temp_dir().join(format!("common-cli-stream-{}", process::id()));
+        fs::create_dir_all(&day_dir).unwrap();
+        fs::write(day_dir.join("input.txt"), "a\nbb\nccc\n").unwrap();
+        let lines = |input: &mut dyn BufRead| Ok(input.lines().count().to_string());
+        let first = |input: &mut dyn BufRead| {
+            let mut line = String::new();
+            input.read_line(&mut line).unwrap();
+            Ok(line.trim_end().to_string())
+        };
+        let whole = solve_record(3, 1, &day_dir, &Input::Puzzle, |input| {
+            Ok(input.lines().count().to_string())
+        });
+        for (solve, answer) in [(lines as StreamSolver, "3"), (first, "a")] {
+            let streamed = stream_record(3, 1, &day_dir, &Input::Puzzle, solve);
+            assert_eq!(streamed.answer.as_deref(), Ok(answer));
+            assert_eq!(streamed.input_hash, whole.input_hash);
+        }
+        let missing = stream_record(3, 1, &day_dir, &Input::Example(1), lines);
+        assert!(missing.answer.is_err() && missing.input_hash.is_none());
+        fs::remove_dir_all(&day_dir).unwrap();
+    }
+}
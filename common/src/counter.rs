@@ -0,0 +1,192 @@
+use std::collections::{btree_map, BTreeMap};
+
+use crate::{OrOverflow, Overflow};
+
+/// A multiset: how many times each key occurs, with the keys kept in order. Keys whose count
+/// drops to zero are forgotten. Counts are `u64`s, and everything that adds to them fails with
+/// [`Overflow`] instead of wrapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<K> {
+    counts: BTreeMap<K, u64>,
+}
+
+impl<K: Ord> Counter<K> {
+    pub fn new() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `count` more of `key`.
+    pub fn add(&mut self, key: K, count: u64) -> Result<(), Overflow> {
+        if count > 0 {
+            let current = self.counts.entry(key).or_default();
+            *current = current.checked_add(count).or_overflow("a count")?;
+        }
+        Ok(())
+    }
+
+    /// Takes away up to `count` of `key`, returning how many there were to take.
+    pub fn remove(&mut self, key: &K, count: u64) -> u64 {
+        let Some(current) = self.counts.get_mut(key) else {
+            return 0;
+        };
+        let removed = count.min(*current);
+        *current -= removed;
+        if *current == 0 {
+            self.counts.remove(key);
+        }
+        removed
+    }
+
+    /// How many times `key` occurs, 0 if it does not.
+    pub fn get(&self, key: &K) -> u64 {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// Adds every key of `other`, as many times as it occurs there.
+    pub fn merge(&mut self, other: Self) -> Result<(), Overflow> {
+        for (key, count) in other {
+            self.add(key, count)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces every key by the keys `f` turns it into, each with a weight: every occurrence of
+    /// the old key adds that many of the new one.
+    pub fn map_keys<L: Ord, I: IntoIterator<Item = (L, u64)>>(
+        self,
+        mut f: impl FnMut(&K) -> I,
+    ) -> Result<Counter<L>, Overflow> {
+        let mut mapped = Counter::new();
+        for (key, count) in self {
+            for (new_key, weight) in f(&key) {
+                mapped.add(new_key, count.checked_mul(weight).or_overflow("a count")?)?;
+            }
+        }
+        Ok(mapped)
+    }
+
+    /// The keys and how many times each occurs, from the smallest key up.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64)> {
+        self.counts.iter().map(|(key, &count)| (key, count))
+    }
+
+    /// How many keys there are, counting every occurrence.
+    pub fn total(&self) -> Result<u64, Overflow> {
+        self.counts
+            .values()
+            .try_fold(0_u64, |total, &count| total.checked_add(count))
+            .or_overflow("the total count")
+    }
+
+    /// How many different keys there are.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl<K: Ord> Default for Counter<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, keys: I) {
+        for key in keys {
+            self.add(key, 1)
+                .expect("counting one key at a time never reaches u64::MAX");
+        }
+    }
+}
+
+impl<K: Ord> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(keys);
+        counter
+    }
+}
+
+/// The keys and their counts, from the smallest key up.
+impl<K> IntoIterator for Counter<K> {
+    type Item = (K, u64);
+    type IntoIter = btree_map::IntoIter<K, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_keys() {
+        let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!((counter.len(), counter.total()), (5, Ok(11)));
+        counter.add('z', 2).unwrap();
+        counter.add('y', 0).unwrap();
+        assert_eq!(counter.remove(&'r', 1), 1);
+        assert_eq!(counter.remove(&'b', 5), 2);
+        assert_eq!(counter.remove(&'q', 1), 0);
+        let counts = counter.iter().map(|(&c, n)| (c, n)).collect::<Vec<_>>();
+        assert_eq!(counts, [('a', 5), ('c', 1), ('d', 1), ('r', 1), ('z', 2)]);
+        assert!(Counter::<char>::default().is_empty());
+    }
+
+    #[test]
+    fn merges_counters() {
+        let mut counter = [3, 1, 3].into_iter().collect::<Counter<u8>>();
+        counter.merge([1, 2].into_iter().collect()).unwrap();
+        assert_eq!(
+            counter.into_iter().collect::<Vec<_>>(),
+            [(1, 2), (2, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn fans_keys_out() {
+        let counter = [10, 10, 7].into_iter().collect::<Counter<u32>>();
+        // every even number splits into two halves, every odd one into three of the next number
+        let mapped = counter
+            .map_keys(|&n| {
+                if n % 2 == 0 {
+                    vec![(n / 2, 2)]
+                } else {
+                    vec![(n + 1, 3)]
+                }
+            })
+            .unwrap();
+        assert_eq!(mapped.into_iter().collect::<Vec<_>>(), [(5, 4), (8, 3)]);
+        let collapsed = [1, 2, 3].into_iter().collect::<Counter<u32>>();
+        let collapsed = collapsed.map_keys(|_| [(0, 1), (0, 1)]).unwrap();
+        assert_eq!(collapsed.get(&0), 6);
+        let emptied = collapsed.map_keys(|_| Vec::<(u8, u64)>::new()).unwrap();
+        assert_eq!(emptied.total(), Ok(0));
+    }
+
+    #[test]
+    fn reports_overflow() {
+        let mut counter = Counter::new();
+        counter.add('a', u64::MAX).unwrap();
+        let error = counter.add('a', 1).unwrap_err();
+        assert_eq!(error.to_string(), "arithmetic overflow computing a count");
+        assert_eq!(counter.get(&'a'), u64::MAX);
+        counter.add('b', 1).unwrap();
+        assert_eq!(
+            counter.total().unwrap_err().to_string(),
+            "arithmetic overflow computing the total count"
+        );
+        assert!(counter.clone().map_keys(|&c| [(c, 2)]).is_err());
+        assert!(counter.map_keys(|_| [('z', 1)]).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a sequence of states, each one following from the last, starts going round in circles:
+/// the state after `start + length` steps is the state after `start` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as the one after `step` steps.
+    pub fn earliest_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle of the states from `initial` on with Floyd's tortoise and hare, holding no
+/// more than two states at a time. The states have to come round eventually, or this never
+/// returns.
+pub fn floyd<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // the hare runs twice as fast, so they meet inside the cycle, a whole number of cycles apart
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+    // which makes the hare as far from the start of the cycle as the initial state is
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Like [`floyd`], with Brent's algorithm, which takes fewer steps.
+pub fn brent<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // the tortoise waits for the hare at every power of two steps, until the hare comes round
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // a hare one cycle ahead of the tortoise meets it at the start of the cycle
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Finds the cycle of the states from `initial` on by remembering every state, so each step is
+/// only taken once. `step` returns `None` to end the sequence, which then has no cycle.
+pub fn find_cycle<T: Clone + Eq + Hash>(
+    initial: T,
+    mut step: impl FnMut(&T) -> Option<T>,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut index = 0;
+    loop {
+        if let Some(start) = seen.insert(state.clone(), index) {
+            return Some(Cycle {
+                start,
+                length: index - start,
+            });
+        }
+        state = step(&state)?;
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Squaring modulo 1000 from 3 gives 3, 9, 81, 561, 721, 841, 281, 961, 521, 441, 481, 361,
+    /// 321, 41, 681, 761, 121, 641, 881, 161, 921, 241, 81, ...
+    fn square(&n: &u64) -> u64 {
+        n * n % 1000
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let expected = Cycle {
+            start: 2,
+            length: 20,
+        };
+        assert_eq!(floyd(3, square), expected);
+        assert_eq!(brent(3, square), expected);
+        assert_eq!(find_cycle(3, |n| Some(square(n))), Some(expected));
+        let fixed = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(floyd(0, square), fixed);
+        assert_eq!(brent(0, square), fixed);
+        let rotation = |&n: &u32| (n + 7) % 100;
+        assert_eq!(
+            brent(5, rotation),
+            Cycle {
+                start: 0,
+                length: 100
+            }
+        );
+        assert_eq!(
+            floyd(5, rotation),
+            Cycle {
+                start: 0,
+                length: 100
+            }
+        );
+    }
+
+    #[test]
+    fn sequences_can_end() {
+        let halve = |&n: &u32| (n > 1).then_some(n / 2);
+        assert_eq!(find_cycle(1000, halve), None);
+        // 27 reaches 4 after 109 steps, and then goes round 4, 2, 1
+        let collatz = |&n: &u64| Some(if n % 2 == 0 { n / 2 } else { 3 * n + 1 });
+        assert_eq!(
+            find_cycle(27, collatz),
+            Some(Cycle {
+                start: 109,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn skips_whole_cycles() {
+        let cycle = brent(3, square);
+        assert_eq!(cycle.earliest_step(1), 1);
+        assert_eq!(cycle.earliest_step(22), 2);
+        assert_eq!(cycle.earliest_step(1_000_000_000), 2 + (999_999_998 % 20));
+        let mut n = 3;
+        for _ in 0..cycle.earliest_step(1_000_000_000) {
+            n = square(&n);
+        }
+        assert_eq!(n, 921);
+    }
+}
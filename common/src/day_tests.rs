@@ -0,0 +1,61 @@
+/// The checks every day runs, as `#[test]` functions in the module it is invoked in, which has to
+/// see the day's type. The examples get a test for each file and part, written by the day's
+/// `build.rs` with [`write_example_tests`](crate::write_example_tests), in the `examples`
+/// module. The day's own tests go next to it:
+///
+/// ```ignore
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     common::day_tests!(Day07, stream, parallel);
+/// }
+/// ```
+///
+/// `stream` adds [`check_stream`](crate::check_stream) for days that implement
+/// [`Stream`](crate::Stream), and `parallel` adds [`check_parallel`](crate::check_parallel) for
+/// days that split their work with [`par_map`](crate::par_map).
+#[macro_export]
+macro_rules! day_tests {
+    ($day:ident $(, $check:ident)* $(,)?) => {
+        mod examples {
+            type Day = super::$day;
+
+            include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+        }
+
+        #[test]
+        fn line_endings() {
+            $crate::check_line_endings::<$day>(".");
+        }
+
+        #[test]
+        fn mutated_examples() {
+            $crate::check_mutations::<$day>(".");
+        }
+
+        #[test]
+        fn generator() {
+            $crate::check_generator::<$day>();
+        }
+
+        #[test]
+        fn matches_reference() {
+            $crate::check_reference::<$day>();
+        }
+
+        $($crate::day_tests!(@check $day, $check);)*
+    };
+    (@check $day:ident, stream) => {
+        #[test]
+        fn streams_like_whole_input() {
+            $crate::check_stream::<$day>(".");
+        }
+    };
+    (@check $day:ident, parallel) => {
+        #[test]
+        fn parallel_matches_sequential() {
+            $crate::check_parallel::<$day>(".");
+        }
+    };
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+
+use nom::{
+    error::{ErrorKind, VerboseError, VerboseErrorKind},
+    Offset,
+};
+
+/// The result type of every day's nom parsers; errors keep track of what was expected where.
+pub type IResult<I, O> = nom::IResult<I, O, VerboseError<I>>;
+
+const SNIPPET_LENGTH: usize = 20;
+
+/// A parse failure pointing at the offending position in the puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Builds an error located at `fragment`, which must be a slice of `input`.
+    pub fn at(input: &str, fragment: &str, expected: impl Into<String>) -> Self {
+        let offset = input.offset(fragment);
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = input[line_start..offset].chars().count() + 1;
+        let rest = input[offset..].lines().next().unwrap_or_default();
+        let mut snippet = rest.chars().take(SNIPPET_LENGTH).collect::<String>();
+        if rest.chars().nth(SNIPPET_LENGTH).is_some() {
+            snippet.push('…');
+        }
+        Self {
+            line,
+            column,
+            snippet,
+            expected: expected.into(),
+        }
+    }
+
+    fn from_verbose(input: &str, error: VerboseError<&str>) -> Self {
+        let Some((fragment, kind)) = error.errors.first() else {
+            return Self::at(input, input, "valid input");
+        };
+        let mut expected = describe(kind);
+        let context = error.errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some(*context),
+            _ => None,
+        });
+        if let Some(context) = context {
+            if !matches!(kind, VerboseErrorKind::Context(_)) {
+                expected = format!("{expected} in {context}");
+            }
+        }
+        Self::at(input, fragment, expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        if self.snippet.is_empty() {
+            write!(f, "found end of line")
+        } else {
+            write!(f, "found `{}`", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An intermediate result or answer too large for the integers a day computes it with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    /// What was being computed, like "the total calibration result".
+    pub computing: String,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow computing {}", self.computing)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Turns the `None` of a `checked_*` operation into an [`Overflow`].
+pub trait OrOverflow<T> {
+    fn or_overflow(self, computing: &str) -> Result<T, Overflow>;
+}
+
+impl<T> OrOverflow<T> for Option<T> {
+    fn or_overflow(self, computing: &str) -> Result<T, Overflow> {
+        self.ok_or_else(|| Overflow {
+            computing: computing.to_string(),
+        })
+    }
+}
+
+/// Why a part could not be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    Overflow(Overflow),
+    /// The input could not be read while it was being streamed.
+    Read(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Overflow(e) => e.fmt(f),
+            Self::Read(e) => write!(f, "cannot read the input: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<Overflow> for SolveError {
+    fn from(e: Overflow) -> Self {
+        Self::Overflow(e)
+    }
+}
+
+fn describe(kind: &VerboseErrorKind) -> String {
+    match kind {
+        VerboseErrorKind::Context(context) => context.to_string(),
+        VerboseErrorKind::Char('\n') => "a line break".to_string(),
+        VerboseErrorKind::Char(c) => format!("`{c}`"),
+        VerboseErrorKind::Nom(kind) => match kind {
+            ErrorKind::Tag => "literal text".to_string(),
+            ErrorKind::Digit => "a number".to_string(),
+            ErrorKind::OneOf => "one of the allowed characters".to_string(),
+            ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".to_string(),
+            ErrorKind::Alpha => "letters".to_string(),
+            ErrorKind::Eof => "end of input".to_string(),
+            kind => kind.description().to_lowercase(),
+        },
+    }
+}
+
+/// Turns the outcome of a top-level parser into a [`ParseError`], rejecting unparsed trailing input.
+///
+/// A list that stops at a malformed item leaves it as trailing input, which is only reported as
+/// not being the end of input. Lists should `cut` the item after each separator, so the error
+/// is the furthest one, inside the item.
+pub fn finish<'a, T>(input: &'a str, result: IResult<&'a str, T>) -> Result<T, ParseError> {
+    match result {
+        Ok((rest, _)) if !rest.is_empty() => {
+            let unexpected = rest.trim_start_matches('\n');
+            let unexpected = if unexpected.is_empty() {
+                rest
+            } else {
+                unexpected
+            };
+            Err(ParseError::at(input, unexpected, "end of input"))
+        }
+        Ok((_, parsed)) => Ok(parsed),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::from_verbose(input, e)),
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::at(input, &input[input.len()..], "more input"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{
+        character::complete::{char as nomchar, newline, u32 as nomu32},
+        combinator::cut,
+        error::context,
+        multi::separated_list1,
+        sequence::separated_pair,
+    };
+
+    fn pairs(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
+        separated_list1(
+            newline,
+            cut(context(
+                "pair",
+                separated_pair(nomu32, nomchar(','), nomu32),
+            )),
+        )(input)
+    }
+
+    #[test]
+    fn reports_position_and_expectation() {
+        let input = "1,2\n3;4";
+        let error = finish(input, pairs(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "`,` in pair");
+        assert_eq!(error.snippet, ";4");
+    }
+
+    #[test]
+    fn reports_trailing_input() {
+        let input = "1,2\n3,4 5,6";
+        let error = finish(input, pairs(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "end of input");
+    }
+
+    #[test]
+    fn reports_innermost_failure() {
+        let input = "1;2\n3,4";
+        let error = finish(input, pairs(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        assert_eq!(error.expected, "`,` in pair");
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 2: expected `,` in pair, found `;2`"
+        );
+    }
+
+    #[test]
+    fn truncates_long_snippets() {
+        let input = "x".repeat(50);
+        let error = ParseError::at(&input, &input[10..], "nothing");
+        assert_eq!(error.column, 11);
+        assert_eq!(error.snippet, format!("{}…", "x".repeat(20)));
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    line_ending_variants, normalize, try_solve_part1, try_solve_part2, Answers, Solution, Solver,
+};
+
+/// Whether `name` looks like `part<N>-example<M>.txt`.
+fn is_example(name: &str) -> bool {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    name.strip_prefix("part")
+        .and_then(|rest| rest.strip_suffix(".txt"))
+        .and_then(|rest| rest.split_once("-example"))
+        .is_some_and(|(part, example)| is_number(part) && is_number(example))
+}
+
+/// Every `part<N>-example<M>.txt` in `dir`, sorted by name.
+pub fn example_files(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+    let dir = dir.as_ref();
+    let entries =
+        fs::read_dir(dir).unwrap_or_else(|e| panic!("cannot read {}: {e}", dir.display()));
+    let mut files = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_example)
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()))
+}
+
+/// The answers in the `.expected` sidecar of the example at `path`.
+fn expected_answers(path: &Path) -> Answers {
+    let sidecar = path.with_extension("expected");
+    Answers::parse(&read(&sidecar)).unwrap_or_else(|e| panic!("{}: {e}", sidecar.display()))
+}
+
+/// Solves `part` of the example at `path` and checks it against the answer in its `.expected`
+/// sidecar.
+pub fn check_example<S: Solution>(path: impl AsRef<Path>, part: u8) {
+    let path = path.as_ref();
+    let expected = expected_answers(path);
+    let (expected, solve): (_, Solver) = match part {
+        1 => (expected.part1, try_solve_part1::<S>),
+        2 => (expected.part2, try_solve_part2::<S>),
+        _ => panic!("there is no part {part}"),
+    };
+    let case = format!("{} part {part}", path.display());
+    let expected = expected.unwrap_or_else(|| panic!("{case}: no answer in the sidecar"));
+    match solve(&read(path)) {
+        Ok(actual) => assert_eq!(actual, expected, "{case}"),
+        Err(e) => panic!("{case}: {e}"),
+    }
+}
+
+/// Writes a module of tests for the examples of the day being built, for
+/// [`day_tests!`](crate::day_tests) to include: one module per example, named after its file,
+/// with a [`check_example`] test for each part its sidecar answers. Called from a day's
+/// `build.rs`, so that adding an example adds its tests.
+pub fn write_example_tests() {
+    let dir = env::var_os("CARGO_MANIFEST_DIR").expect("build scripts know their package");
+    let out_dir = env::var_os("OUT_DIR").expect("build scripts have an output directory");
+    let dir = PathBuf::from(dir);
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let files = example_files(&dir);
+    let mut tests = String::new();
+    if files.is_empty() {
+        let message = format!("no example files in {}", dir.display());
+        tests.push_str(&format!(
+            "#[test]\nfn none_found() {{\n    panic!({message:?});\n}}\n"
+        ));
+    }
+    for path in files {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let sidecar = path.with_extension("expected");
+        // an unreadable sidecar is reported by the tests, for both parts
+        let answers = fs::read_to_string(&sidecar)
+            .ok()
+            .and_then(|contents| Answers::parse(&contents).ok());
+        let parts = match answers {
+            Some(answers) => [answers.part1.map(|_| 1), answers.part2.map(|_| 2)],
+            None => [Some(1), Some(2)],
+        };
+        tests.push_str(&format!(
+            "mod {} {{\n",
+            name.trim_end_matches(".txt").replace('-', "_")
+        ));
+        for part in parts.into_iter().flatten() {
+            tests.push_str(&format!(
+                "    #[test]\n    fn part{part}() {{\n        \
+                 common::check_example::<super::Day>({name:?}, {part});\n    }}\n"
+            ));
+        }
+        tests.push_str("}\n");
+    }
+    let out = Path::new(&out_dir).join("examples.rs");
+    if fs::read_to_string(&out).ok().as_deref() != Some(tests.as_str()) {
+        fs::write(&out, tests).unwrap_or_else(|e| panic!("cannot write {}: {e}", out.display()));
+    }
+}
+
+/// Checks that every example in `dir` solves with each of its line ending variants, to the same
+/// answers as with plain LF line endings.
+pub fn check_line_endings<S: Solution>(dir: impl AsRef<Path>) {
+    for path in example_files(dir) {
+        let input = normalize(&read(&path)).into_owned();
+        let expected = expected_answers(&path);
+        let cases: [(u8, Option<String>, Solver); 2] = [
+            (1, expected.part1, try_solve_part1::<S>),
+            (2, expected.part2, try_solve_part2::<S>),
+        ];
+        for (part, expected, solve) in cases {
+            if expected.is_none() {
+                continue;
+            }
+            let answer =
+                solve(&input).unwrap_or_else(|e| panic!("{} part {part}: {e}", path.display()));
+            for variant in line_ending_variants(&input) {
+                match solve(&variant) {
+                    Ok(variant_answer) => assert_eq!(
+                        variant_answer,
+                        answer,
+                        "{} part {part} as {variant:?}",
+                        path.display()
+                    ),
+                    Err(e) => panic!("{} part {part} as {variant:?}: {e}", path.display()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_example_names() {
+        assert!(is_example("part1-example1.txt"));
+        assert!(is_example("part2-example12.txt"));
+        assert!(!is_example("part1-example1.expected"));
+        assert!(!is_example("part2-dont.txt"));
+        assert!(!is_example("part-example1.txt"));
+        assert!(!is_example("input.txt"));
+    }
+}
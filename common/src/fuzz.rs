@@ -0,0 +1,62 @@
+use std::{fs, panic, path::Path};
+
+use crate::{example_files, try_solve_part1, try_solve_part2, Solution};
+
+/// Characters that [`mutations`] swaps in, picked to break numbers, grids and separators.
+const TROUBLEMAKERS: [char; 9] = ['0', '1', '9', '-', '.', '#', ' ', '\n', 'x'];
+
+/// The body of a fuzz target: parses `input` and solves both parts. Any outcome but a panic is
+/// fine.
+pub fn fuzz<S: Solution>(input: &str) {
+    let _ = try_solve_part1::<S>(input);
+    let _ = try_solve_part2::<S>(input);
+}
+
+/// Variations of `input` that reach the corners of a parser: every prefix, and `input` with each
+/// character deleted or replaced by one of a few troublemakers.
+pub fn mutations(input: &str) -> impl Iterator<Item = String> + '_ {
+    let boundaries = input.char_indices().map(|(i, c)| (i, i + c.len_utf8()));
+    boundaries.flat_map(move |(start, end)| {
+        let (before, after) = (&input[..start], &input[end..]);
+        [before.to_string(), format!("{before}{after}")]
+            .into_iter()
+            .chain(TROUBLEMAKERS.map(|c| format!("{before}{c}{after}")))
+    })
+}
+
+/// Fuzzes the solution offline with every example in `dir` and all of its [`mutations`],
+/// reporting every input that panicked.
+pub fn check_mutations<S: Solution>(dir: impl AsRef<Path>) {
+    let mut failures = Vec::new();
+    for path in example_files(dir) {
+        let example = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+        for input in std::iter::once(example.clone()).chain(mutations(&example)) {
+            if panic::catch_unwind(|| fuzz::<S>(&input)).is_err() {
+                failures.push(format!("{}: {input:?}", path.display()));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} input(s) panicked:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutates_every_character() {
+        let mutations = mutations("ab").collect::<Vec<_>>();
+        assert_eq!(mutations.len(), 2 * (2 + TROUBLEMAKERS.len()));
+        assert!(mutations.contains(&String::new()));
+        assert!(mutations.contains(&"a".to_string()));
+        assert!(mutations.contains(&"b".to_string()));
+        assert!(mutations.contains(&"a9".to_string()));
+        assert!(mutations.contains(&"-b".to_string()));
+    }
+}
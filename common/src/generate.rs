@@ -0,0 +1,112 @@
+use std::ops::RangeInclusive;
+
+use crate::{normalize, Solution};
+
+/// A small, fast and reproducible SplitMix64 generator; the same seed always gives the same
+/// inputs, on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`; `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "cannot pick a number below zero");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "cannot pick from an empty range");
+        let span = end.abs_diff(start).wrapping_add(1);
+        match span {
+            0 => self.next_u64() as i64,
+            span => start.wrapping_add((self.next_u64() % span) as i64),
+        }
+    }
+
+    /// `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Produces random but well-formed puzzle inputs, to benchmark and fuzz a day's solver beyond its
+/// single real input.
+pub trait Generate {
+    /// An input in the day's format, ending in a newline. `size` scales it roughly linearly,
+    /// in a unit each day documents (lines, records, or the side of a grid); every size from 1
+    /// up gives a valid input.
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
+
+/// The sizes of generated inputs that [`check_generator`] and `check_reference` try.
+pub(crate) const CHECKED_SIZES: [usize; 6] = [1, 2, 3, 5, 10, 20];
+
+/// Checks that generated inputs of a range of seeds and sizes parse; [`check_reference`] also
+/// solves them.
+///
+/// [`check_reference`]: crate::check_reference
+pub fn check_generator<S: Solution + Generate>() {
+    for seed in 0..8 {
+        for size in CHECKED_SIZES {
+            let input = S::generate(&mut Rng::new(seed), size);
+            assert!(
+                input.ends_with('\n'),
+                "seed {seed}, size {size}: no final newline"
+            );
+            if let Err(e) = S::parse(&normalize(&input)) {
+                panic!("seed {seed}, size {size}: {e}\n{input}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_reproducible_and_in_range() {
+        let first = (0..4).map(|_| Rng::new(7).next_u64()).collect::<Vec<_>>();
+        assert!(first.windows(2).all(|pair| pair[0] == pair[1]));
+        let mut rng = Rng::new(1);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((-2..=2).contains(&rng.range(-2..=2)));
+        }
+        assert_eq!(rng.range(5..=5), 5);
+        rng.range(i64::MIN..=i64::MAX);
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+        let mut items = [1, 2, 3, 4, 5];
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+pub(crate) const BOM: char = '\u{feff}';
+
+/// Strips a byte order mark, converts CRLF line endings to LF and drops trailing line endings,
+/// so parsers only ever see the canonical form of a puzzle input. Spaces and tabs are kept, even
+/// at the end of the last line.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let mut input = input.strip_prefix(BOM).unwrap_or(input);
+    while let Some(rest) = input.strip_suffix('\n') {
+        input = rest.strip_suffix('\r').unwrap_or(rest);
+    }
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// The same input with CRLF line endings, trailing newlines and a byte order mark.
+pub fn line_ending_variants(input: &str) -> Vec<String> {
+    let crlf = input.replace('\n', "\r\n");
+    vec![
+        format!("{input}\n"),
+        format!("{input}\n\n"),
+        crlf.clone(),
+        format!("{crlf}\r\n"),
+        format!("{BOM}{input}\n"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_variants() {
+        let input = "1 2\n3 4";
+        for variant in line_ending_variants(input) {
+            assert_eq!(normalize(&variant), input);
+        }
+    }
+
+    #[test]
+    fn keeps_trailing_whitespace() {
+        assert_eq!(normalize("1 2\n3\t4 \t\r\n\n"), "1 2\n3\t4 \t");
+        assert_eq!(normalize("1 2\n \n\n"), "1 2\n ");
+        assert_eq!(normalize("\n\r\n"), "");
+    }
+
+    #[test]
+    fn borrows_canonical_input() {
+        assert!(matches!(normalize("1 2\n3 4"), Cow::Borrowed(_)));
+    }
+}
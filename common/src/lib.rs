@@ -0,0 +1,86 @@
+use std::fmt::Display;
+
+mod answers;
+mod cli;
+mod counter;
+mod cycle;
+mod day_tests;
+mod error;
+mod examples;
+mod fuzz;
+mod generate;
+mod input;
+mod number_theory;
+mod output;
+mod parallel;
+mod progress;
+mod reference;
+mod stream;
+
+pub use answers::Answers;
+pub use cli::{run_part, run_streamed_part, solve_record, stream_record, Input};
+pub use counter::Counter;
+pub use cycle::{brent, find_cycle, floyd, Cycle};
+pub use error::{finish, IResult, OrOverflow, Overflow, ParseError, SolveError};
+pub use examples::{check_example, check_line_endings, example_files, write_example_tests};
+pub use fuzz::{check_mutations, fuzz, mutations};
+pub use generate::{check_generator, Generate, Rng};
+pub use input::{line_ending_variants, normalize};
+pub use number_theory::{crt, extended_gcd, gcd, lcm, mod_inverse, solve_2x2, Integer};
+pub use output::{input_hash, Format, Record};
+pub use parallel::{check_parallel, is_parallel, par_map, sequential};
+#[cfg(feature = "progress-bar")]
+pub use progress::TerminalProgress;
+pub use progress::{
+    progress, report_to_terminal, set_progress, with_progress, NoProgress, Progress,
+};
+pub use reference::{check_reference, Reference};
+pub use stream::{
+    check_stream, try_stream_part1, try_stream_part2, Separator, Stream, StreamSolver,
+};
+
+/// Solves one part of a day from its raw puzzle input, like [`try_solve_part1`].
+pub type Solver = fn(&str) -> Result<String, SolveError>;
+
+/// A day's puzzle: the input is parsed once and both parts are solved from the parsed form.
+pub trait Solution {
+    type Parsed;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    /// Parses an input that has already been through [`normalize`].
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+
+    fn part1(parsed: &Self::Parsed) -> Self::Answer1;
+
+    fn part2(parsed: &Self::Parsed) -> Self::Answer2;
+
+    /// Solves part 1, reporting arithmetic overflow instead of panicking or wrapping. Days whose
+    /// inputs can make their integers overflow implement this, and `part1` in terms of it.
+    fn checked_part1(parsed: &Self::Parsed) -> Result<Self::Answer1, Overflow> {
+        Ok(Self::part1(parsed))
+    }
+
+    /// Solves part 2 like [`Solution::checked_part1`].
+    fn checked_part2(parsed: &Self::Parsed) -> Result<Self::Answer2, Overflow> {
+        Ok(Self::part2(parsed))
+    }
+}
+
+pub fn try_solve_part1<S: Solution>(input: &str) -> Result<String, SolveError> {
+    let parsed = S::parse(&normalize(input))?;
+    Ok(S::checked_part1(&parsed)?.to_string())
+}
+
+pub fn try_solve_part2<S: Solution>(input: &str) -> Result<String, SolveError> {
+    let parsed = S::parse(&normalize(input))?;
+    Ok(S::checked_part2(&parsed)?.to_string())
+}
+
+pub fn solve_part1<S: Solution>(input: &str) -> String {
+    try_solve_part1::<S>(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn solve_part2<S: Solution>(input: &str) -> String {
+    try_solve_part2::<S>(input).unwrap_or_else(|e| panic!("{e}"))
+}
@@ -0,0 +1,266 @@
+use std::fmt::Debug;
+
+use crate::{OrOverflow, Overflow};
+
+/// The signed primitive integers, as far as the number theory here needs them.
+pub trait Integer: Copy + Ord + Debug {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_rem_euclid(self, rhs: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                self.checked_add(rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                self.checked_sub(rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                self.checked_mul(rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                self.checked_div(rhs)
+            }
+
+            fn checked_rem(self, rhs: Self) -> Option<Self> {
+                self.checked_rem(rhs)
+            }
+
+            fn checked_rem_euclid(self, rhs: Self) -> Option<Self> {
+                self.checked_rem_euclid(rhs)
+            }
+
+            fn checked_abs(self) -> Option<Self> {
+                self.checked_abs()
+            }
+        }
+    )*};
+}
+
+integer!(i8, i16, i32, i64, i128, isize);
+
+/// `a % b`, where the one remainder that overflows, of the smallest value divided by -1, is 0.
+fn rem<T: Integer>(a: T, b: T) -> T {
+    a.checked_rem(b).unwrap_or(T::ZERO)
+}
+
+/// The greatest common divisor, never negative; `gcd(0, 0)` is 0. Panics if that is the
+/// smallest value's magnitude, which does not fit.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, rem(a, b));
+    }
+    a.checked_abs()
+        .expect("the greatest common divisor should fit")
+}
+
+/// The least common multiple, never negative; 0 if either number is.
+pub fn lcm<T: Integer>(a: T, b: T) -> Result<T, Overflow> {
+    if a == T::ZERO || b == T::ZERO {
+        return Ok(T::ZERO);
+    }
+    a.checked_div(gcd(a, b))
+        .and_then(|a| a.checked_mul(b))
+        .and_then(T::checked_abs)
+        .or_overflow("a least common multiple")
+}
+
+/// The greatest common divisor `g` of `a` and `b`, with `x` and `y` such that
+/// `a * x + b * y == g`, by the extended Euclidean algorithm. Panics if a number on the way
+/// does not fit, which only happens with the smallest value.
+pub fn extended_gcd<T: Integer>(a: T, b: T) -> (T, T, T) {
+    let step = |(previous, current): (T, T), quotient: T| {
+        let next = quotient
+            .checked_mul(current)
+            .and_then(|product| previous.checked_sub(product))
+            .expect("the Bézout coefficients should fit");
+        (current, next)
+    };
+    let (mut r, mut x, mut y) = ((a, b), (T::ONE, T::ZERO), (T::ZERO, T::ONE));
+    while r.1 != T::ZERO {
+        let quotient = r.0.checked_div(r.1).expect("the quotients should fit");
+        (r, x, y) = (step(r, quotient), step(x, quotient), step(y, quotient));
+    }
+    if r.0 < T::ZERO {
+        let negate = |n: T| T::ZERO.checked_sub(n).expect("the gcd should fit");
+        (negate(r.0), negate(x.0), negate(y.0))
+    } else {
+        (r.0, x.0, y.0)
+    }
+}
+
+/// The `x` in `0..m` with `a * x` one more than a multiple of `m`, if `a` and `m` have no
+/// common divisor. Panics unless `m` is positive.
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    assert!(m > T::ZERO, "the modulus {m:?} should be positive");
+    let a = a.checked_rem_euclid(m)?;
+    let (g, x, _) = extended_gcd(a, m);
+    (g == T::ONE).then(|| x.checked_rem_euclid(m).expect("m is positive"))
+}
+
+/// The numbers that leave remainder `r` divided by `m`, for every `(r, m)` in `congruences`, by
+/// the Chinese remainder theorem: all numbers that leave the returned remainder divided by the
+/// returned modulus, the least common multiple of the `m`. The moduli can share divisors, in
+/// which case there may be no such numbers at all. Panics unless every `m` is positive.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Result<Option<(T, T)>, Overflow> {
+    let overflow = || Overflow {
+        computing: "a Chinese remainder".to_string(),
+    };
+    let mut solution = (T::ZERO, T::ONE);
+    for &(r, m) in congruences {
+        assert!(m > T::ZERO, "the modulus {m:?} should be positive");
+        let (r0, m0) = solution;
+        let r = r.checked_rem_euclid(m).ok_or_else(overflow)?;
+        // r0 + m0 * k leaves r divided by m, for the k that leave some remainder divided by m / g
+        let g = gcd(m0, m);
+        let difference = r.checked_sub(r0).ok_or_else(overflow)?;
+        if rem(difference, g) != T::ZERO {
+            return Ok(None);
+        }
+        let (m0_g, m_g) = (m0.checked_div(g), m.checked_div(g));
+        let (m0_g, m_g) = m0_g.zip(m_g).ok_or_else(overflow)?;
+        let inverse = mod_inverse(m0_g, m_g).expect("m0 / g and m / g have no common divisor");
+        let k = difference
+            .checked_div(g)
+            .and_then(|d| d.checked_rem_euclid(m_g))
+            .and_then(|d| d.checked_mul(inverse))
+            .and_then(|k| k.checked_rem_euclid(m_g))
+            .ok_or_else(overflow)?;
+        let modulus = m0.checked_mul(m_g).ok_or_else(overflow)?;
+        let residue = m0
+            .checked_mul(k)
+            .and_then(|step| r0.checked_add(step))
+            .and_then(|x| x.checked_rem_euclid(modulus))
+            .ok_or_else(overflow)?;
+        solution = (residue, modulus);
+    }
+    Ok(Some(solution))
+}
+
+/// The whole numbers `[x, y]` with `a * x + b * y == e` and `c * x + d * y == f`, for
+/// `[[a, b], [c, d]]` and `[e, f]`, by Cramer's rule. `None` unless there is exactly one
+/// solution and it is whole.
+pub fn solve_2x2<T: Integer>(
+    [[a, b], [c, d]]: [[T; 2]; 2],
+    [e, f]: [T; 2],
+) -> Result<Option<[T; 2]>, Overflow> {
+    let cross = |p: T, q: T, r: T, s: T| {
+        let (pq, rs) = (p.checked_mul(q), r.checked_mul(s));
+        pq.zip(rs)
+            .and_then(|(pq, rs)| pq.checked_sub(rs))
+            .or_overflow("a system of linear equations")
+    };
+    let determinant = cross(a, d, b, c)?;
+    if determinant == T::ZERO {
+        return Ok(None);
+    }
+    let divide = |n: T| match n.checked_rem(determinant) {
+        Some(remainder) if remainder != T::ZERO => Ok(None),
+        _ => n
+            .checked_div(determinant)
+            .map(Some)
+            .or_overflow("a system of linear equations"),
+    };
+    let (x, y) = (divide(cross(e, d, b, f)?)?, divide(cross(a, f, e, c)?)?);
+    Ok(x.zip(y).map(|(x, y)| [x, y]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisors_and_multiples() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, -7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(i64::MIN, -1), 1);
+        assert_eq!(lcm(4, 6), Ok(12));
+        assert_eq!(lcm(-4, 6), Ok(12));
+        assert_eq!(lcm(0, 6), Ok(0));
+        assert!(lcm(i32::MAX, i32::MAX - 1).is_err());
+    }
+
+    #[test]
+    fn bezout_coefficients() {
+        for (a, b) in [
+            (240, 46),
+            (46, 240),
+            (-240, 46),
+            (7, 0),
+            (0, -7),
+            (17, 17),
+            (1, 1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "{a}, {b}");
+            assert_eq!(a * x + b * y, g, "{a}, {b}");
+        }
+        let (g, x, y) = extended_gcd(i64::MAX, i64::MAX - 1);
+        assert_eq!(g, 1);
+        assert_eq!(
+            i128::from(i64::MAX) * i128::from(x) + i128::from(i64::MAX - 1) * i128::from(y),
+            1
+        );
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        for a in 1..101 {
+            assert_eq!(mod_inverse(a, 101).map(|x| a * x % 101), Some(1));
+        }
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok(Some((23, 105))));
+        assert_eq!(crt(&[(13, 101), (79, 103)]), Ok(Some((7083, 10403))));
+        // moduli sharing a divisor
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Ok(Some((11, 12))));
+        assert_eq!(crt(&[(3, 4), (4, 6)]), Ok(None));
+        assert_eq!(crt(&[(-2, 4), (10, 8)]), Ok(Some((2, 8))));
+        assert_eq!(crt::<i32>(&[]), Ok(Some((0, 1))));
+        let huge = (1_i64 << 40) + 15;
+        assert!(crt(&[(1, huge), (2, huge + 2)]).is_err());
+        let (r, m) = crt(&[(1_i128, i128::from(huge)), (2, i128::from(huge) + 2)])
+            .unwrap()
+            .unwrap();
+        assert_eq!((r % i128::from(huge), r % (i128::from(huge) + 2)), (1, 2));
+        assert_eq!(m, i128::from(huge) * (i128::from(huge) + 2));
+    }
+
+    #[test]
+    fn linear_systems() {
+        // 80 presses of A and 40 of B in the first claw machine of day 13
+        assert_eq!(
+            solve_2x2([[94, 22], [34, 67]], [8400, 5400]),
+            Ok(Some([80, 40]))
+        );
+        assert_eq!(solve_2x2([[26, 67], [66, 21]], [12748, 12176]), Ok(None));
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), Ok(None));
+        assert_eq!(solve_2x2([[2, 0], [0, -3]], [-4, 9]), Ok(Some([-2, -3])));
+        assert!(solve_2x2([[i64::MAX, 2], [2, i64::MAX]], [1, 1]).is_err());
+    }
+}
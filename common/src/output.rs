@@ -0,0 +1,152 @@
+use std::{fmt::Write, time::Duration};
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per line and part, see [`Record::to_json`].
+    Json,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown format `{other}`, expected `text` or `json`"
+            )),
+        }
+    }
+}
+
+/// A stable 64-bit FNV-1a hash of `input`, as 16 hex digits, to tell inputs apart in reports.
+pub fn input_hash(input: &str) -> String {
+    let mut hasher = InputHasher::default();
+    hasher.update(input.as_bytes());
+    hasher.finish()
+}
+
+/// Computes [`input_hash`] a chunk at a time, for inputs that are streamed.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InputHasher(u64);
+
+impl Default for InputHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl InputHasher {
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        self.0 = bytes.iter().fold(self.0, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    }
+
+    pub(crate) fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// The outcome of solving one part of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    /// Where the input came from.
+    pub input: String,
+    /// `None` when the input could not be read.
+    pub input_hash: Option<String>,
+    pub elapsed: Option<Duration>,
+    pub answer: Result<String, String>,
+}
+
+impl Record {
+    /// A single-line JSON object with the day, part, status (`ok` or `error`), answer, error,
+    /// elapsed nanoseconds, input and input hash.
+    pub fn to_json(&self) -> String {
+        let (status, answer, error) = match &self.answer {
+            Ok(answer) => ("ok", Some(answer.as_str()), None),
+            Err(error) => ("error", None, Some(error.as_str())),
+        };
+        let mut json = format!(
+            "{{\"day\":{},\"part\":{},\"status\":\"{status}\"",
+            self.day, self.part
+        );
+        write!(json, ",\"answer\":{}", json_string(answer)).unwrap();
+        write!(json, ",\"error\":{}", json_string(error)).unwrap();
+        match self.elapsed {
+            Some(elapsed) => write!(json, ",\"elapsed_ns\":{}", elapsed.as_nanos()),
+            None => write!(json, ",\"elapsed_ns\":null"),
+        }
+        .unwrap();
+        write!(json, ",\"input\":{}", json_string(Some(&self.input))).unwrap();
+        write!(
+            json,
+            ",\"input_hash\":{}}}",
+            json_string(self.input_hash.as_deref())
+        )
+        .unwrap();
+        json
+    }
+}
+
+fn json_string(s: Option<&str>) -> String {
+    let Some(s) = s else {
+        return "null".to_string();
+    };
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_stable() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn records_as_json() {
+        let mut record = Record {
+            day: 3,
+            part: 2,
+            input: "day-03/input.txt".to_string(),
+            input_hash: Some(input_hash("x")),
+            elapsed: Some(Duration::from_micros(5)),
+            answer: Ok("48".to_string()),
+        };
+        assert_eq!(
+            record.to_json(),
+            format!(
+                "{{\"day\":3,\"part\":2,\"status\":\"ok\",\"answer\":\"48\",\"error\":null,\
+                 \"elapsed_ns\":5000,\"input\":\"day-03/input.txt\",\"input_hash\":\"{}\"}}",
+                input_hash("x")
+            )
+        );
+        record.answer = Err("expected \"a\"\n\tfound `\u{1}`".to_string());
+        record.elapsed = None;
+        assert!(record.to_json().contains(
+            "\"status\":\"error\",\"answer\":null,\
+             \"error\":\"expected \\\"a\\\"\\n\\tfound `\\u0001`\",\"elapsed_ns\":null"
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{cell::Cell, fs, path::Path};
+
+use crate::{
+    example_files, generate::CHECKED_SIZES, try_solve_part1, try_solve_part2, Generate, Rng,
+    Solution, Solver,
+};
+
+thread_local! {
+    static SEQUENTIAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the previous mode when [`sequential`] returns or unwinds.
+struct Restore(bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        SEQUENTIAL.set(self.0);
+    }
+}
+
+/// Runs `f` with [`par_map`] working sequentially on this thread, to compare both paths.
+pub fn sequential<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(SEQUENTIAL.replace(true));
+    f()
+}
+
+/// Whether [`par_map`] spreads its work over threads here: with the `parallel` feature, outside
+/// of [`sequential`].
+pub fn is_parallel() -> bool {
+    cfg!(feature = "parallel") && !SEQUENTIAL.get()
+}
+
+/// `f` applied to every item, in order. The items are independent units of work, spread over
+/// rayon's thread pool with the `parallel` feature.
+pub fn par_map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    #[cfg(feature = "parallel")]
+    if is_parallel() {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    items.iter().map(f).collect()
+}
+
+/// Checks that the examples in `dir` and a range of generated inputs give the same answers (or
+/// errors) whether [`par_map`] runs in parallel or not. Streaming, which solves records one by
+/// one, is held to both by [`check_stream`](crate::check_stream).
+pub fn check_parallel<S: Solution + Generate>(dir: impl AsRef<Path>) {
+    let examples = example_files(dir).into_iter().map(|path| {
+        let input = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+        (path.display().to_string(), input)
+    });
+    let generated = (0..4).flat_map(|seed| {
+        CHECKED_SIZES.map(|size| {
+            let input = S::generate(&mut Rng::new(seed), size);
+            (format!("seed {seed}, size {size}"), input)
+        })
+    });
+    let parts: [(u8, Solver); 2] = [(1, try_solve_part1::<S>), (2, try_solve_part2::<S>)];
+    for (name, input) in examples.chain(generated) {
+        for (part, solve) in parts {
+            assert_eq!(
+                solve(&input),
+                sequential(|| solve(&input)),
+                "{name} part {part}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_in_order_both_ways() {
+        let items = (0..1000).collect::<Vec<u64>>();
+        let squares = items.iter().map(|n| n * n).collect::<Vec<_>>();
+        assert_eq!(par_map(&items, |n| n * n), squares);
+        assert_eq!(sequential(|| par_map(&items, |n| n * n)), squares);
+        assert!(!sequential(is_parallel));
+        assert_eq!(is_parallel(), cfg!(feature = "parallel"));
+    }
+}
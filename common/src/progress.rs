@@ -0,0 +1,155 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, RwLock},
+};
+
+/// Receives progress from long-running solvers, so that front ends can show it while library
+/// callers and tests stay quiet.
+pub trait Progress: Send + Sync {
+    /// A task of `len` steps begins, replacing any unfinished one.
+    fn start(&self, label: &str, len: u64);
+
+    /// `steps` more steps of the current task are done.
+    fn advance(&self, steps: u64);
+
+    fn finish(&self);
+}
+
+/// Ignores all progress; the reporter unless [`set_progress`] or [`with_progress`] installs another.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn start(&self, _label: &str, _len: u64) {}
+
+    fn advance(&self, _steps: u64) {}
+
+    fn finish(&self) {}
+}
+
+/// A progress bar on stderr, which indicatif hides when stderr is not a terminal.
+#[cfg(feature = "progress-bar")]
+#[derive(Debug, Default)]
+pub struct TerminalProgress {
+    bar: std::sync::Mutex<Option<indicatif::ProgressBar>>,
+}
+
+#[cfg(feature = "progress-bar")]
+impl Progress for TerminalProgress {
+    fn start(&self, label: &str, len: u64) {
+        let bar = indicatif::ProgressBar::new(len).with_message(label.to_string());
+        bar.set_style(
+            indicatif::ProgressStyle::default_bar()
+                .template("{msg} [{elapsed_precise}] [{bar:50}] {pos}/{len}")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        if let Some(old) = self.bar.lock().unwrap().replace(bar) {
+            old.finish_and_clear();
+        }
+    }
+
+    fn advance(&self, steps: u64) {
+        if let Some(bar) = self.bar.lock().unwrap().as_ref() {
+            bar.inc(steps);
+        }
+    }
+
+    fn finish(&self) {
+        if let Some(bar) = self.bar.lock().unwrap().take() {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+static DEFAULT: RwLock<Option<Arc<dyn Progress>>> = RwLock::new(None);
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<dyn Progress>>> = const { RefCell::new(None) };
+}
+
+/// Makes `reporter` receive all progress from now on, on every thread, replacing the one set
+/// before. Calls inside [`with_progress`] keep reporting to the reporter of their scope.
+pub fn set_progress(reporter: impl Progress + 'static) {
+    *DEFAULT.write().unwrap() = Some(Arc::new(reporter));
+}
+
+/// Runs `f` with `reporter` receiving the progress of the solvers it calls on this thread, like
+/// [`NoProgress`] to silence a single call, whatever [`set_progress`] installed.
+pub fn with_progress<T>(reporter: impl Progress + 'static, f: impl FnOnce() -> T) -> T {
+    /// Puts the reporter of the enclosing scope back, even if `f` panics.
+    struct Restore(Option<Arc<dyn Progress>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(SCOPED.replace(Some(Arc::new(reporter))));
+    f()
+}
+
+/// The reporter of the innermost [`with_progress`] on this thread, else the one installed by
+/// [`set_progress`], else [`NoProgress`]. Solvers fetch it before spreading their work over
+/// threads, so that the work reports to their caller's reporter.
+pub fn progress() -> Arc<dyn Progress> {
+    SCOPED
+        .with_borrow(Option::clone)
+        .or_else(|| DEFAULT.read().unwrap().clone())
+        .unwrap_or_else(|| Arc::new(NoProgress))
+}
+
+/// Shows progress on the terminal when built with the `progress-bar` feature, and does nothing
+/// otherwise. Meant for binaries; libraries should leave the choice to their callers.
+pub fn report_to_terminal() {
+    #[cfg(feature = "progress-bar")]
+    set_progress(TerminalProgress::default());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Collects the labels of the tasks started, in a log shared with the test.
+    #[derive(Clone, Default)]
+    struct Labels(Arc<Mutex<Vec<String>>>);
+
+    impl Progress for Labels {
+        fn start(&self, label: &str, _len: u64) {
+            self.0.lock().unwrap().push(label.to_string());
+        }
+
+        fn advance(&self, _steps: u64) {}
+
+        fn finish(&self) {}
+    }
+
+    // one test, since the default reporter is shared by every thread
+    #[test]
+    fn scopes_override_the_default() {
+        let (default, scoped) = (Labels::default(), Labels::default());
+        progress().start("unreported", 1);
+        set_progress(default.clone());
+        progress().start("first", 1);
+        with_progress(scoped.clone(), || {
+            progress().start("second", 1);
+            with_progress(NoProgress, || progress().start("silenced", 1));
+            progress().start("third", 1);
+            std::thread::spawn(|| progress().start("other thread", 1))
+                .join()
+                .unwrap();
+        });
+        let result = std::panic::catch_unwind(|| with_progress(NoProgress, || panic!("solver")));
+        assert!(result.is_err());
+        progress().start("fourth", 1);
+        *DEFAULT.write().unwrap() = None;
+        assert_eq!(
+            *default.0.lock().unwrap(),
+            ["first", "other thread", "fourth"]
+        );
+        assert_eq!(*scoped.0.lock().unwrap(), ["second", "third"]);
+    }
+}
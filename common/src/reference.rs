@@ -0,0 +1,32 @@
+use crate::{generate::CHECKED_SIZES, normalize, Generate, Rng, Solution};
+
+/// A deliberately simple, obviously correct way to solve a day, kept next to the real solution
+/// so that it can be optimized without fear.
+pub trait Reference: Solution {
+    fn reference_part1(parsed: &Self::Parsed) -> Self::Answer1;
+
+    fn reference_part2(parsed: &Self::Parsed) -> Self::Answer2;
+}
+
+/// Checks that the real and the reference solutions agree on generated inputs of a range of seeds
+/// and sizes.
+pub fn check_reference<S: Solution + Generate + Reference>() {
+    for seed in 0..8 {
+        for size in CHECKED_SIZES {
+            let input = S::generate(&mut Rng::new(seed), size);
+            let input = normalize(&input);
+            let parsed = S::parse(&input)
+                .unwrap_or_else(|e| panic!("seed {seed}, size {size}: {e}\n{input}"));
+            assert_eq!(
+                S::part1(&parsed).to_string(),
+                S::reference_part1(&parsed).to_string(),
+                "part 1, seed {seed}, size {size}:\n{input}"
+            );
+            assert_eq!(
+                S::part2(&parsed).to_string(),
+                S::reference_part2(&parsed).to_string(),
+                "part 2, seed {seed}, size {size}:\n{input}"
+            );
+        }
+    }
+}
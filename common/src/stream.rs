@@ -0,0 +1,287 @@
+use std::{
+    fs,
+    io::{self, BufRead},
+    ops::Range,
+    path::Path,
+};
+
+use crate::{
+    example_files, generate::CHECKED_SIZES, input::BOM, line_ending_variants, mutations, normalize,
+    sequential, try_solve_part1, try_solve_part2, Generate, Overflow, ParseError, Rng, Solution,
+    SolveError, Solver,
+};
+
+/// Solves one part of a day while reading its raw puzzle input, like [`try_stream_part1`].
+pub type StreamSolver = fn(&mut dyn BufRead) -> Result<String, SolveError>;
+
+/// What separates the records of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// Every line is a record.
+    Line,
+    /// Records are blocks of lines with a blank line between them.
+    BlankLine,
+}
+
+/// A day whose input is a run of records that can be solved as they are read, keeping no more
+/// of the input around than the parts need. Streaming a day gives the same answers and errors as
+/// [`Solution::parse`] and the parts do with the whole input, except that an input with several
+/// problems reports the first one read, where the whole input reports parse errors first.
+pub trait Stream: Solution {
+    const SEPARATOR: Separator;
+
+    type Record;
+    /// What part 1 keeps of the records read so far.
+    type Tally1: Default;
+    /// What part 2 keeps of the records read so far.
+    type Tally2: Default;
+
+    /// Parses one record, reporting errors as if it were the whole input.
+    fn parse_record(record: &str) -> Result<Self::Record, ParseError>;
+
+    fn tally1(tally: &mut Self::Tally1, record: &Self::Record) -> Result<(), Overflow>;
+
+    fn tally2(tally: &mut Self::Tally2, record: &Self::Record) -> Result<(), Overflow>;
+
+    fn answer1(tally: Self::Tally1) -> Result<Self::Answer1, Overflow>;
+
+    fn answer2(tally: Self::Tally2) -> Result<Self::Answer2, Overflow>;
+}
+
+pub fn try_stream_part1<S: Stream>(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    let tally = tally::<S, _>(input, S::tally1)?;
+    Ok(S::answer1(tally)?.to_string())
+}
+
+pub fn try_stream_part2<S: Stream>(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    let tally = tally::<S, _>(input, S::tally2)?;
+    Ok(S::answer2(tally)?.to_string())
+}
+
+fn tally<S: Stream, T: Default>(
+    input: &mut dyn BufRead,
+    add: fn(&mut T, &S::Record) -> Result<(), Overflow>,
+) -> Result<T, SolveError> {
+    let mut tally = T::default();
+    let mut empty = true;
+    for record in Records::new(input, S::SEPARATOR) {
+        let (line, record) = record.map_err(|e| SolveError::Read(e.to_string()))?;
+        let record = S::parse_record(&record).map_err(|e| ParseError {
+            line: e.line + line - 1,
+            ..e
+        })?;
+        add(&mut tally, &record)?;
+        empty = false;
+    }
+    if empty {
+        // an empty input is wrong (or right) in the same way as it is for the whole-input parser
+        S::parse("")?;
+    }
+    Ok(tally)
+}
+
+/// The records of an input, each with the number of its first line, normalized as by
+/// [`normalize`](crate::normalize): without byte order mark, CRLF line endings or trailing empty
+/// lines. Only the current record is held in memory.
+struct Records<'a> {
+    lines: Lines<'a>,
+    separator: Separator,
+}
+
+impl<'a> Records<'a> {
+    fn new(input: &'a mut dyn BufRead, separator: Separator) -> Self {
+        Self {
+            lines: Lines::new(input),
+            separator,
+        }
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.separator == Separator::Line {
+            return self.lines.next();
+        }
+        // split on every `\n\n`, so that a second blank line starts a record of its own
+        let mut block: Option<(usize, String)> = None;
+        loop {
+            let (number, line) = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return block.map(Ok),
+            };
+            match &mut block {
+                Some(_) if line.is_empty() => return block.map(Ok),
+                Some((_, block)) => {
+                    block.push('\n');
+                    block.push_str(&line);
+                }
+                None => block = Some((number, line)),
+            }
+        }
+    }
+}
+
+/// The numbered lines of an input. The last line that is not empty is held back until the next
+/// one turns up, since the empty lines after it are dropped at the end of the input. Those are
+/// only counted, so a long run of them takes no memory.
+struct Lines<'a> {
+    input: &'a mut dyn BufRead,
+    number: usize,
+    held: Option<(usize, String)>,
+    /// The numbers of the empty lines after the held one.
+    empty: Range<usize>,
+    /// The line read after the empty ones, which shows that they are not at the end.
+    next: Option<(usize, String)>,
+    done: bool,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a mut dyn BufRead) -> Self {
+        Self {
+            input,
+            number: 0,
+            held: None,
+            empty: 0..0,
+            next: None,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.next.is_some() {
+                if let Some(line) = self.held.take() {
+                    return Some(Ok(line));
+                }
+                if let Some(number) = self.empty.next() {
+                    return Some(Ok((number, String::new())));
+                }
+                self.held = self.next.take();
+            }
+            if self.done {
+                return self.held.take().map(Ok);
+            }
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) => {
+                    self.done = true;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            if self.number == 0 && line.starts_with(BOM) {
+                line.remove(0);
+            }
+            self.number += 1;
+            if !line.is_empty() {
+                self.next = Some((self.number, line));
+            } else if self.empty.is_empty() {
+                self.empty = self.number..self.number + 1;
+            } else {
+                self.empty.end += 1;
+            }
+        }
+    }
+}
+
+/// Checks that streaming gives the same answers and errors as parsing the whole input, both with
+/// the parts spread over threads and run [`sequential`]ly, for the examples in `dir` with all
+/// their line ending variants and mutations, and for generated inputs.
+pub fn check_stream<S: Stream + Generate>(dir: impl AsRef<Path>) {
+    let mut inputs = Vec::new();
+    for path in example_files(dir) {
+        let example = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+        let name = path.display().to_string();
+        let variants = line_ending_variants(&normalize(&example));
+        inputs.extend(variants.into_iter().map(|v| (format!("{name} variant"), v)));
+        inputs.extend(mutations(&example).map(|m| (format!("{name} mutated"), m)));
+        inputs.push((name, example));
+    }
+    for seed in 0..4 {
+        for size in CHECKED_SIZES {
+            let input = S::generate(&mut Rng::new(seed), size);
+            inputs.push((format!("seed {seed}, size {size}"), input));
+        }
+    }
+    let parts: [(u8, Solver, StreamSolver); 2] = [
+        (1, try_solve_part1::<S>, try_stream_part1::<S>),
+        (2, try_solve_part2::<S>, try_stream_part2::<S>),
+    ];
+    for (name, input) in inputs {
+        for (part, solve, stream) in parts {
+            let streamed = stream(&mut input.as_bytes());
+            assert_eq!(streamed, solve(&input), "{name} part {part}:\n{input:?}");
+            assert_eq!(
+                streamed,
+                sequential(|| solve(&input)),
+                "{name} part {part} run sequentially:\n{input:?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(input: &str, separator: Separator) -> Vec<(usize, String)> {
+        Records::new(&mut input.as_bytes(), separator)
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn normalizes_lines() {
+        let input = "\u{feff}1 2\r\n  \r\n3 4 \t\n\n\r\n";
+        let lines = records(input, Separator::Line);
+        assert_eq!(
+            lines,
+            [(1, "1 2".into()), (2, "  ".into()), (3, "3 4 \t".into())]
+        );
+        let joined = lines.into_iter().map(|(_, line)| line).collect::<Vec<_>>();
+        assert_eq!(joined.join("\n"), normalize(input));
+        assert_eq!(records(" \n\n", Separator::Line), [(1, " ".into())]);
+        assert!(records("\n\n", Separator::Line).is_empty());
+        let numbers = records("\n\na\n\n\nb\n\n", Separator::Line)
+            .into_iter()
+            .map(|(number, _)| number)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn splits_blocks_like_str_split() {
+        let input = "a\nb\n\nc\n\n\nd\n\n\n\ne\n";
+        let blocks = records(input, Separator::BlankLine);
+        assert_eq!(
+            blocks,
+            [
+                (1, "a\nb".into()),
+                (4, "c".into()),
+                (6, "\nd".into()),
+                (9, "".into()),
+                (11, "e".into())
+            ]
+        );
+        let texts = blocks.into_iter().map(|(_, block)| block);
+        assert!(texts.eq(normalize(input).split("\n\n").map(String::from)));
+    }
+}
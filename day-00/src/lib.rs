@@ -1,49 +1,87 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::char as nomchar,
-    character::complete::{alpha1, newline, one_of, space0, space1},
-    combinator::recognize,
-    multi::{many1, separated_list1},
-    IResult,
-};
+use common::{Generate, IResult, ParseError, Reference, Rng, Solution, SolveError};
+
+use nom::character::complete::alpha1;
+use parsing::{lines, spaced};
+
+pub struct Day00;
+
+impl Solution for Day00 {
+    type Parsed = Vec<Line>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(_lines: &Self::Parsed) -> String {
+        "".to_string()
+    }
+
+    fn part2(_lines: &Self::Parsed) -> String {
+        "".to_string()
+    }
+}
+
+/// `size` is the number of lines.
+impl Generate for Day00 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size {
+            let words = (0..rng.range(1..=5))
+                .map(|_| {
+                    (0..rng.range(1..=8))
+                        .map(|_| (b'a' + rng.below(26) as u8) as char)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            input.push_str(&words.join(" "));
+            input.push('\n');
+        }
+        input
+    }
+}
+
+impl Reference for Day00 {
+    fn reference_part1(_lines: &Self::Parsed) -> String {
+        "".to_string()
+    }
+
+    fn reference_part2(_lines: &Self::Parsed) -> String {
+        "".to_string()
+    }
+}
 
 pub fn process_part1(input: &str) -> String {
-    "".to_string()
+    common::solve_part1::<Day00>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    "".to_string()
+    common::solve_part2::<Day00>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day00>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day00>(input)
 }
 
-type Line<'a> = Vec<&'a str>;
+pub type Line = Vec<String>;
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+    lines(parse_line)(input)
 }
 
 fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, line) = separated_list1(space1, alpha1)(input)?;
-    Ok((input, line))
+    let (input, line) = spaced(alpha1)(input)?;
+    Ok((input, line.into_iter().map(String::from).collect()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "");
-    }
-
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part2-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "");
-    }
+    common::day_tests!(Day00);
 }
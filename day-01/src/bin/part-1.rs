@@ -1,7 +1,10 @@
-use answer::process_part1;
-use std::fs;
+use day_01::{stream_part1, try_process_part1};
 
 fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part1(&file));
+    common::run_streamed_part(
+        1,
+        env!("CARGO_MANIFEST_DIR"),
+        try_process_part1,
+        stream_part1,
+    );
 }
@@ -1,40 +1,185 @@
-use itertools::Itertools;
+use std::{io::BufRead, iter};
 
-use nom::{
-    character::complete::{newline, space1, u32 as nomu32},
-    multi::separated_list1,
-    sequence::tuple,
-    IResult,
+use common::{
+    Counter, Generate, IResult, OrOverflow, Overflow, ParseError, Reference, Rng, Separator,
+    Solution, SolveError, Stream,
 };
 
+use nom::{character::complete::space1, sequence::tuple};
+use parsing::{lines, unsigned};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Parsed = (Vec<u32>, Vec<u32>);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(parsed: &Self::Parsed) -> u64 {
+        Self::checked_part1(parsed).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> u64 {
+        Self::checked_part2(parsed).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn checked_part1((first, second): &Self::Parsed) -> Result<u64, Overflow> {
+        let mut first = first.clone();
+        let mut second = second.clone();
+        first.sort();
+        second.sort();
+        distance(first, second)
+    }
+
+    fn checked_part2((first, second): &Self::Parsed) -> Result<u64, Overflow> {
+        let counts = |ids: &[u32]| ids.iter().copied().collect::<Counts>();
+        similarity(counts(first), counts(second))
+    }
+}
+
+/// Streaming keeps how often each ID occurs in either list, so memory is bounded by the number of
+/// distinct IDs instead of the length of the lists.
+impl Stream for Day01 {
+    const SEPARATOR: Separator = Separator::Line;
+
+    type Record = Line;
+    type Tally1 = (Counts, Counts);
+    type Tally2 = (Counts, Counts);
+
+    fn parse_record(line: &str) -> Result<Line, ParseError> {
+        common::finish(line, parse_line(line))
+    }
+
+    fn tally1((first, second): &mut Self::Tally1, &(f, s): &Line) -> Result<(), Overflow> {
+        first.add(f, 1)?;
+        second.add(s, 1)?;
+        Ok(())
+    }
+
+    fn tally2(tally: &mut Self::Tally2, line: &Line) -> Result<(), Overflow> {
+        Self::tally1(tally, line)
+    }
+
+    fn answer1((first, second): Self::Tally1) -> Result<u64, Overflow> {
+        let sorted = |counts: Counts| {
+            counts
+                .into_iter()
+                .flat_map(|(id, n)| iter::repeat_n(id, n as usize))
+        };
+        distance(sorted(first), sorted(second))
+    }
+
+    fn answer2((first, second): Self::Tally2) -> Result<u64, Overflow> {
+        similarity(first, second)
+    }
+}
+
+/// `size` is the number of location ID pairs; about a third of the right list repeats IDs from
+/// the left one, so the similarity score is not always zero.
+impl Generate for Day01 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let left = (0..size)
+            .map(|_| rng.range(10_000..=99_999))
+            .collect::<Vec<_>>();
+        let mut input = String::new();
+        for &id in &left {
+            let other = if rng.chance(0.3) {
+                *rng.choose(&left)
+            } else {
+                rng.range(10_000..=99_999)
+            };
+            input.push_str(&format!("{id}   {other}\n"));
+        }
+        input
+    }
+}
+
+impl Reference for Day01 {
+    /// Pairs up the lists by repeatedly taking the smallest ID left in each.
+    fn reference_part1((first, second): &Self::Parsed) -> u64 {
+        fn take_smallest(ids: &mut Vec<u32>) -> Option<u32> {
+            let i = (0..ids.len()).min_by_key(|&i| ids[i])?;
+            Some(ids.swap_remove(i))
+        }
+        let (mut first, mut second) = (first.clone(), second.clone());
+        let mut distance = 0;
+        while let (Some(f), Some(s)) = (take_smallest(&mut first), take_smallest(&mut second)) {
+            distance += u64::from(f.abs_diff(s));
+        }
+        distance
+    }
+
+    fn reference_part2((first, second): &Self::Parsed) -> u64 {
+        first
+            .iter()
+            .map(|&f| u64::from(f) * second.iter().filter(|&&s| s == f).count() as u64)
+            .sum()
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, (mut first, mut second)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    first.sort();
-    second.sort();
-    let total_difference = first
-        .into_iter()
-        .zip(second)
-        .map(|(f, s)| f.abs_diff(s))
-        .sum::<u32>();
-    total_difference.to_string()
+    common::solve_part1::<Day01>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, (first, second)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let second = second.into_iter().counts();
-    let total = first
+    common::solve_part2::<Day01>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day01>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day01>(input)
+}
+
+pub fn stream_part1(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part1::<Day01>(input)
+}
+
+pub fn stream_part2(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part2::<Day01>(input)
+}
+
+type Line = (u32, u32);
+
+/// How many times each ID occurs in a list.
+type Counts = Counter<u32>;
+
+/// The similarity score: every ID of the first list times how often it is in the second one.
+fn similarity(first: Counts, second: Counts) -> Result<u64, Overflow> {
+    first
         .into_iter()
-        .filter_map(|f| second.get(&f).map(|s| f * (*s as u32)))
-        .sum::<u32>();
-    total.to_string()
+        .map(|(id, n)| (u64::from(id), n, second.get(&id)))
+        .try_fold(0_u64, |total, (id, n, m)| {
+            id.checked_mul(n)
+                .and_then(|score| score.checked_mul(m))
+                .and_then(|score| total.checked_add(score))
+                .or_overflow("the similarity score")
+        })
 }
 
-type Line<'a> = (u32, u32);
+/// The total distance between two sorted lists.
+fn distance(
+    first: impl IntoIterator<Item = u32>,
+    second: impl IntoIterator<Item = u32>,
+) -> Result<u64, Overflow> {
+    first
+        .into_iter()
+        .zip(second)
+        .try_fold(0_u64, |total, (f, s)| {
+            total
+                .checked_add(u64::from(f.abs_diff(s)))
+                .or_overflow("the total distance")
+        })
+}
 
 fn parse_input(input: &str) -> IResult<&str, (Vec<u32>, Vec<u32>)> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
+    let (input, lines) = lines(parse_line)(input)?;
     let mut first = Vec::with_capacity(lines.len());
     let mut second = Vec::with_capacity(lines.len());
     lines.into_iter().for_each(|(f, s)| {
@@ -45,7 +190,7 @@ fn parse_input(input: &str) -> IResult<&str, (Vec<u32>, Vec<u32>)> {
 }
 
 fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, (first, _, second)) = tuple((nomu32, space1, nomu32))(input)?;
+    let (input, (first, _, second)) = tuple((unsigned, space1, unsigned))(input)?;
     let line = (first, second);
     Ok((input, line))
 }
@@ -53,19 +198,28 @@ fn parse_line(input: &str) -> IResult<&str, Line> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+
+    common::day_tests!(Day01, stream);
 
     #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "11");
+    fn invalid_input() {
+        let Err(SolveError::Parse(error)) = try_process_part1("3   4\n4   x3\n2   5") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "a number");
     }
 
     #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "31");
+    fn large_ids() {
+        let max = u32::MAX;
+        assert_eq!(
+            process_part1(&format!("{max}   0\n{max}   0")),
+            "8589934590"
+        );
+        assert_eq!(
+            process_part2(&format!("{max}   {max}\n{max}   {max}")),
+            "17179869180"
+        );
     }
 }
@@ -1,22 +1,22 @@
-use nom::{
-    character::complete::u8 as nomu8,
-    character::complete::{newline, space1},
-    multi::separated_list1,
-    IResult,
+use std::io::BufRead;
+
+use common::{
+    Generate, IResult, Overflow, ParseError, Reference, Rng, Separator, Solution, SolveError,
+    Stream,
 };
 
+use parsing::{lines, spaced, unsigned};
+
 trait IsSafe: Iterator<Item = u8> {
     fn is_safe(&mut self) -> bool
     where
         Self: Sized,
     {
         let iter = self;
-        let first = iter
-            .next()
-            .expect("Report must have at least two levels to be checked for safety");
-        let second = iter
-            .next()
-            .expect("Report must have at least two levels to be checked for safety");
+        // a report of a single level never changes, so it cannot change unsafely
+        let (Some(first), Some(second)) = (iter.next(), iter.next()) else {
+            return true;
+        };
         let range = 1u8..4;
         if !range.contains(&first.abs_diff(second)) {
             return false;
@@ -53,54 +53,166 @@ trait SkipAny: Iterator<Item = u8> {
 
 impl<I> SkipAny for I where I: Iterator<Item = u8> {}
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Parsed = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(reports: &Self::Parsed) -> usize {
+        common::par_map(reports, |report| safe(report))
+            .into_iter()
+            .filter(|&safe| safe)
+            .count()
+    }
+
+    fn part2(reports: &Self::Parsed) -> usize {
+        common::par_map(reports, |report| safe_with_dampener(report))
+            .into_iter()
+            .filter(|&safe| safe)
+            .count()
+    }
+}
+
+impl Stream for Day02 {
+    const SEPARATOR: Separator = Separator::Line;
+
+    type Record = Line;
+    /// The number of safe reports.
+    type Tally1 = usize;
+    type Tally2 = usize;
+
+    fn parse_record(line: &str) -> Result<Line, ParseError> {
+        common::finish(line, parse_line(line))
+    }
+
+    fn tally1(count: &mut usize, report: &Line) -> Result<(), Overflow> {
+        *count += usize::from(safe(report));
+        Ok(())
+    }
+
+    fn tally2(count: &mut usize, report: &Line) -> Result<(), Overflow> {
+        *count += usize::from(safe_with_dampener(report));
+        Ok(())
+    }
+
+    fn answer1(safe: usize) -> Result<usize, Overflow> {
+        Ok(safe)
+    }
+
+    fn answer2(safe: usize) -> Result<usize, Overflow> {
+        Ok(safe)
+    }
+}
+
+/// `size` is the number of reports. Most reports change gradually in one direction, and some
+/// have a bad level or two.
+impl Generate for Day02 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size {
+            let direction = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level = rng.range(10..=89);
+            let mut levels = vec![level];
+            for _ in 1..rng.range(5..=8) {
+                let step = if rng.chance(0.1) {
+                    rng.range(-3..=6)
+                } else {
+                    rng.range(1..=3)
+                };
+                level = (level + direction * step).clamp(1, 99);
+                levels.push(level);
+            }
+            let levels = levels.iter().map(i64::to_string).collect::<Vec<_>>();
+            input.push_str(&levels.join(" "));
+            input.push('\n');
+        }
+        input
+    }
+}
+
+/// Whether the levels of `report` change gradually in one direction.
+fn safe(report: &[u8]) -> bool {
+    report.iter().copied().is_safe()
+}
+
+/// Whether `report` is safe, or would be with one level left out.
+fn safe_with_dampener(report: &[u8]) -> bool {
+    safe(report) || report.iter().copied().skip_any()
+}
+
+/// Checks a report for the reference by looking at every pair of neighbouring levels.
+fn reference_is_safe(levels: &[u8]) -> bool {
+    let increasing = levels.windows(2).all(|w| w[0] < w[1] && w[1] - w[0] <= 3);
+    let decreasing = levels.windows(2).all(|w| w[0] > w[1] && w[0] - w[1] <= 3);
+    increasing || decreasing
+}
+
+impl Reference for Day02 {
+    fn reference_part1(reports: &Self::Parsed) -> usize {
+        reports
+            .iter()
+            .filter(|levels| reference_is_safe(levels))
+            .count()
+    }
+
+    fn reference_part2(reports: &Self::Parsed) -> usize {
+        reports
+            .iter()
+            .filter(|levels| {
+                reference_is_safe(levels)
+                    || (0..levels.len()).any(|skip| {
+                        let mut levels = levels.to_vec();
+                        levels.remove(skip);
+                        reference_is_safe(&levels)
+                    })
+            })
+            .count()
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, reports) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    reports
-        .into_iter()
-        .filter(|r| r.iter().copied().is_safe())
-        .count()
-        .to_string()
+    common::solve_part1::<Day02>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, reports) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    reports
-        .into_iter()
-        .filter(|r| r.iter().copied().is_safe() || r.iter().copied().skip_any())
-        .count()
-        .to_string()
+    common::solve_part2::<Day02>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day02>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day02>(input)
+}
+
+pub fn stream_part1(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part1::<Day02>(input)
+}
+
+pub fn stream_part2(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part2::<Day02>(input)
 }
 
-type Line<'a> = Vec<u8>;
+type Line = Vec<u8>;
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+    lines(parse_line)(input)
 }
 
 fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, line) = separated_list1(space1, nomu8)(input)?;
-    Ok((input, line))
+    spaced(unsigned)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "2");
-    }
-
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "4");
-    }
+    common::day_tests!(Day02, stream, parallel);
 }
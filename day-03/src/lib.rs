@@ -1,82 +1,247 @@
+use std::io::BufRead;
+
+use common::{
+    Generate, IResult, Overflow, ParseError, Reference, Rng, Separator, Solution, SolveError,
+    Stream,
+};
+
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_until},
-    character::complete::{anychar, u32 as nomu32},
-    combinator::{eof, recognize, value},
-    multi::many1,
-    sequence::{terminated, tuple},
-    IResult,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::anychar,
+    combinator::{map_res, value},
+    multi::{many0, many1},
 };
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Parsed = Vec<Instruction>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(instructions: &Self::Parsed) -> u64 {
+        instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Mul(a, b) => u64::from(a * b),
+                Instruction::Do | Instruction::Dont => 0,
+            })
+            .sum::<u64>()
+    }
+
+    fn part2(instructions: &Self::Parsed) -> u64 {
+        let mut switched = Switched::default();
+        switched.run(instructions);
+        switched.sum
+    }
+}
+
+/// Instructions never span lines, so each line is a record; `do()` and `don't()` carry over from
+/// one line to the next in the tally of part 2.
+impl Stream for Day03 {
+    const SEPARATOR: Separator = Separator::Line;
+
+    type Record = Vec<Instruction>;
+    type Tally1 = u64;
+    type Tally2 = Switched;
+
+    fn parse_record(line: &str) -> Result<Self::Record, ParseError> {
+        common::finish(line, parse_line(line))
+    }
+
+    fn tally1(sum: &mut u64, instructions: &Self::Record) -> Result<(), Overflow> {
+        *sum += Self::part1(instructions);
+        Ok(())
+    }
+
+    fn tally2(tally: &mut Switched, instructions: &Self::Record) -> Result<(), Overflow> {
+        tally.run(instructions);
+        Ok(())
+    }
+
+    fn answer1(sum: u64) -> Result<u64, Overflow> {
+        Ok(sum)
+    }
+
+    fn answer2(tally: Switched) -> Result<u64, Overflow> {
+        Ok(tally.sum)
+    }
+}
+
+/// The sum of the enabled multiplications so far, and whether the last switch was a `don't()`.
+#[derive(Debug, Default)]
+pub struct Switched {
+    disabled: bool,
+    sum: u64,
+}
+
+impl Switched {
+    fn run(&mut self, instructions: &[Instruction]) {
+        for instruction in instructions {
+            match instruction {
+                Instruction::Mul(a, b) if !self.disabled => self.sum += u64::from(a * b),
+                Instruction::Mul(_, _) => {}
+                Instruction::Do => self.disabled = false,
+                Instruction::Dont => self.disabled = true,
+            }
+        }
+    }
+}
+
+/// `size` is the number of `mul` instructions, surrounded by `do()`, `don't()`, near misses and
+/// other junk.
+impl Generate for Day03 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const DECOYS: [&str; 10] = [
+            "mul(4*",
+            "mul[3,7]",
+            "mul ( 2 , 4 )",
+            "?(12,34)",
+            "select()",
+            "from()",
+            "mul(32,64]",
+            "don't",
+            "do(",
+            "what()",
+        ];
+        const JUNK: &[u8] = b"!@#$%^&*()[]{}<>?/'+-;:, ~";
+        let mut input = String::new();
+        for i in 0..size {
+            if i > 0 && i % 50 == 0 {
+                input.push('\n');
+            }
+            for _ in 0..rng.below(6) {
+                input.push(*rng.choose(JUNK) as char);
+            }
+            if rng.chance(0.3) {
+                input.push_str(DECOYS[rng.below(DECOYS.len())]);
+            }
+            if rng.chance(0.1) {
+                input.push_str(if rng.chance(0.5) { "do()" } else { "don't()" });
+            }
+            input.push_str(&format!(
+                "mul({},{})",
+                rng.range(1..=999),
+                rng.range(1..=999)
+            ));
+        }
+        input.push('\n');
+        input
+    }
+}
+
+impl Reference for Day03 {
+    fn reference_part1(instructions: &Self::Parsed) -> u64 {
+        let mut sum = 0;
+        for instruction in instructions {
+            if let Instruction::Mul(a, b) = instruction {
+                sum += u64::from(a * b);
+            }
+        }
+        sum
+    }
+
+    /// Looks back from every `mul` for the last `do()` or `don't()` before it.
+    fn reference_part2(instructions: &Self::Parsed) -> u64 {
+        let mut sum = 0;
+        for (i, instruction) in instructions.iter().enumerate() {
+            if let Instruction::Mul(a, b) = instruction {
+                let last_switch = instructions[..i]
+                    .iter()
+                    .rfind(|switch| !matches!(switch, Instruction::Mul(_, _)));
+                if !matches!(last_switch, Some(Instruction::Dont)) {
+                    sum += u64::from(a * b);
+                }
+            }
+        }
+        sum
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, muls) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let total = muls.into_iter().map(|(a, b)| a * b).sum::<u32>();
-    total.to_string()
+    common::solve_part1::<Day03>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, muls) = parse_input2(input).unwrap();
-    assert!(input.is_empty());
-    let total = muls.into_iter().map(|(a, b)| a * b).sum::<u32>();
-    total.to_string()
+    common::solve_part2::<Day03>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day03>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day03>(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
-    let (input, muls) = many1(alt((parse_mul, value(None, anychar))))(input)?;
-    let muls = muls.into_iter().flatten().collect();
-    Ok((input, muls))
+pub fn stream_part1(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part1::<Day03>(input)
 }
 
-fn parse_input2(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
-    let (input, muls) = many1(alt((parse_dont, parse_mul, value(None, anychar))))(input)?;
-    let muls = muls.into_iter().flatten().collect();
-    Ok((input, muls))
+pub fn stream_part2(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part2::<Day03>(input)
 }
 
-fn parse_dont(input: &str) -> IResult<&str, Option<(u32, u32)>> {
-    let (input, _) = tuple((
-        tag("don't()"),
-        alt((
-            terminated(take_until("do()"), tag("do()")),
-            recognize(terminated(many1(anychar), eof)),
-        )),
-    ))(input)?;
-    Ok((input, None))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
 }
 
-fn parse_mul(input: &str) -> IResult<&str, Option<(u32, u32)>> {
+fn parse_input(input: &str) -> IResult<&str, Vec<Instruction>> {
+    let (input, instructions) = many1(parse_instruction)(input)?;
+    let instructions = instructions.into_iter().flatten().collect();
+    Ok((input, instructions))
+}
+
+/// The instructions on one line, which may have none at all.
+fn parse_line(input: &str) -> IResult<&str, Vec<Instruction>> {
+    let (input, instructions) = many0(parse_instruction)(input)?;
+    let instructions = instructions.into_iter().flatten().collect();
+    Ok((input, instructions))
+}
+
+/// An instruction, or `None` for a character of corrupted memory.
+fn parse_instruction(input: &str) -> IResult<&str, Option<Instruction>> {
+    alt((
+        parse_mul,
+        value(Some(Instruction::Do), tag("do()")),
+        value(Some(Instruction::Dont), tag("don't()")),
+        value(None, anychar),
+    ))(input)
+}
+
+fn parse_mul(input: &str) -> IResult<&str, Option<Instruction>> {
     let (input, _) = tag("mul(")(input)?;
-    let (input, lhs) = nomu32(input)?;
+    let (input, lhs) = operand(input)?;
     let (input, _) = tag(",")(input)?;
-    let (input, rhs) = nomu32(input)?;
+    let (input, rhs) = operand(input)?;
     let (input, _) = tag(")")(input)?;
-    Ok((input, Some((lhs, rhs))))
+    Ok((input, Some(Instruction::Mul(lhs, rhs))))
+}
+
+/// A number of one to three digits; longer ones make the whole instruction corrupted.
+fn operand(input: &str) -> IResult<&str, u32> {
+    map_res(
+        take_while_m_n(1, 3, |c: char| c.is_ascii_digit()),
+        str::parse,
+    )(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "161");
-    }
 
-    #[test]
-    fn dont() {
-        let input = fs::read_to_string("./part2-dont.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "1");
-    }
+    common::day_tests!(Day03, stream);
 
     #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part2-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "48");
+    fn long_operands() {
+        assert_eq!(process_part1("mul(1234,5)mul(2,3)mul(999,0001)"), "6");
     }
 }
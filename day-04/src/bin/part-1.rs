@@ -1,7 +1,5 @@
-use answer::process_part1;
-use std::fs;
+use day_04::try_process_part1;
 
 fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part1(&file));
+    common::run_part(1, env!("CARGO_MANIFEST_DIR"), try_process_part1);
 }
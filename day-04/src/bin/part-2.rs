@@ -1,7 +1,5 @@
-use answer::process_part2;
-use std::fs;
+use day_04::try_process_part2;
 
 fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part2(&file));
+    common::run_part(2, env!("CARGO_MANIFEST_DIR"), try_process_part2);
 }
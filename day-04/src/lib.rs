@@ -1,101 +1,114 @@
-pub fn process_part1(input: &str) -> String {
-    let chars = parse_input(input);
-    let width = chars[0].len();
-    let height = chars.len();
-    let mut string = String::new();
-    // left to right
-    chars.iter().for_each(|c| {
-        string.extend(c.iter().copied());
-        string.push(' ');
-    });
-    // down
-    (0..width).for_each(|x| {
-        (0..height).for_each(|y| string.push(chars[y][x]));
-        string.push(' ');
-    });
-    // positive diagonal
-    (0..height).for_each(|starty| {
-        (0..=starty).rev().for_each(|y| {
-            let x = starty - y;
-            string.push(chars[y][x]);
-        });
-        string.push(' ');
-    });
-    (1..width).for_each(|startx| {
-        (startx..width).enumerate().for_each(|(i, x)| {
-            let y = height - i - 1;
-            string.push(chars[y][x]);
-        });
-        string.push(' ');
-    });
-    // negative diagonal
-    (0..width).rev().for_each(|startx| {
-        (startx..width)
-            .enumerate()
-            .for_each(|(y, x)| string.push(chars[y][x]));
-        string.push(' ');
-    });
-    (1..height).for_each(|starty| {
-        (starty..height).enumerate().for_each(|(x, y)| {
-            string.push(chars[y][x]);
-        });
-        string.push(' ');
-    });
-    let reverse = string.chars().rev().collect::<String>();
-    string.push_str(&reverse);
+use common::{Generate, ParseError, Reference, Rng, Solution, SolveError};
+use grid::Grid;
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Parsed = Grid<char>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parsing::grid(input, Some)
+    }
+
+    fn part1(chars: &Self::Parsed) -> usize {
+        let lines = chars
+            .rows()
+            .map(|row| row.iter().collect::<String>())
+            .chain(chars.columns().map(|column| column.collect()))
+            .chain(chars.diagonals().map(|diagonal| diagonal.collect()))
+            .chain(chars.anti_diagonals().map(|diagonal| diagonal.collect()));
+        lines
+            .map(|line| line.matches("XMAS").count() + line.matches("SAMX").count())
+            .sum()
+    }
 
-    string.matches("XMAS").count().to_string()
+    fn part2(chars: &Self::Parsed) -> usize {
+        let is_mas = |pos, a, b| {
+            matches!(
+                (chars.get_offset(pos, a), chars.get_offset(pos, b)),
+                (Some('M'), Some('S')) | (Some('S'), Some('M'))
+            )
+        };
+        chars
+            .iter()
+            .filter(|&(_, &c)| c == 'A')
+            .filter(|&(pos, _)| is_mas(pos, (-1, -1), (1, 1)) && is_mas(pos, (-1, 1), (1, -1)))
+            .count()
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let chars = parse_input(input);
-    let width = chars[0].len();
-    let height = chars.len();
-    let count = (1..(width - 1))
-        .flat_map(|x| (1..(height - 1)).map(move |y| (x, y)))
-        .filter(|&(x, y)| chars[y][x] == 'A')
-        .filter(|&(x, y)| {
-            (chars[y - 1][x - 1] == 'M' && chars[y + 1][x + 1] == 'S'
-                || chars[y - 1][x - 1] == 'S' && chars[y + 1][x + 1] == 'M')
-                && (chars[y + 1][x - 1] == 'M' && chars[y - 1][x + 1] == 'S'
-                    || chars[y + 1][x - 1] == 'S' && chars[y - 1][x + 1] == 'M')
-        })
-        .count();
-    count.to_string()
+/// `size` is the side of the square word search.
+impl Generate for Day04 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size {
+            input.extend((0..size).map(|_| *rng.choose(&['X', 'M', 'A', 'S'])));
+            input.push('\n');
+        }
+        input
+    }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    let mut table = Vec::new();
-    let mut row = Vec::new();
-    for c in input.chars() {
-        match c {
-            '\n' => {
-                table.push(row);
-                row = Vec::new();
+impl Reference for Day04 {
+    /// Tries to read `XMAS` from every cell in each of the eight directions.
+    fn reference_part1(chars: &Self::Parsed) -> usize {
+        let at = |x: isize, y: isize| {
+            let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+            chars.get((x, y)).copied()
+        };
+        let mut count = 0;
+        for ((x, y), _) in chars.iter() {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    count += usize::from(
+                        (dx, dy) != (0, 0)
+                            && "XMAS".chars().enumerate().all(|(i, c)| {
+                                let i = i as isize;
+                                at(x as isize + dx * i, y as isize + dy * i) == Some(c)
+                            }),
+                    );
+                }
+            }
+        }
+        count
+    }
+
+    /// Reads both diagonals through every `A` that is not on the border.
+    fn reference_part2(chars: &Self::Parsed) -> usize {
+        let mut count = 0;
+        for y in 1..chars.height().saturating_sub(1) {
+            for x in 1..chars.width().saturating_sub(1) {
+                let diagonal = [chars[(x - 1, y - 1)], chars[(x, y)], chars[(x + 1, y + 1)]];
+                let anti_diagonal = [chars[(x + 1, y - 1)], chars[(x, y)], chars[(x - 1, y + 1)]];
+                let is_mas = |word: [char; 3]| word == ['M', 'A', 'S'] || word == ['S', 'A', 'M'];
+                count += usize::from(is_mas(diagonal) && is_mas(anti_diagonal));
             }
-            x => row.push(x),
         }
+        count
     }
-    table.push(row);
-    table
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day04>(input)
+}
+
+pub fn process_part2(input: &str) -> String {
+    common::solve_part2::<Day04>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day04>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day04>(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "18");
-    }
-
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "9");
-    }
+    common::day_tests!(Day04);
 }
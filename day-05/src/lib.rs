@@ -1,97 +1,178 @@
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap};
 
-use nom::{
-    character::complete::{char as nomchar, newline, u8 as nomu8},
-    multi::separated_list1,
-    sequence::{pair, terminated, tuple},
-    IResult,
-};
+use common::{Generate, IResult, ParseError, Reference, Rng, Solution, SolveError};
+use graph::Cycle;
 
-pub fn process_part1(input: &str) -> String {
-    let (input, (rules, updates)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let rules: HashMap<u8, Vec<u8>> =
-        rules
+use nom::{character::complete::char as nomchar, sequence::tuple};
+use parsing::{lines, section, separated, unsigned};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Parsed = Manual;
+    type Answer1 = usize;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let (rules, updates) = common::finish(input, parse_input(input))?;
+        let followers: HashMap<u8, Vec<u8>> =
+            rules
+                .iter()
+                .copied()
+                .fold(HashMap::new(), |mut acc, (before, after)| {
+                    acc.entry(before).or_default().push(after);
+                    acc
+                });
+        let update_lines = input.split_once("\n\n").map_or("", |(_, updates)| updates);
+        let updates = updates
             .into_iter()
-            .fold(HashMap::new(), |mut acc, (before, after)| {
-                acc.entry(after)
-                    .and_modify(|v| v.push(before))
-                    .or_insert(vec![before]);
-                acc
-            });
-    let count: usize = updates
-        .into_iter()
-        .filter_map(|pages| {
-            if is_correctly_ordered(&pages, &rules) {
-                Some(pages[pages.len() / 2] as usize)
-            } else {
-                None
+            .zip(update_lines.lines())
+            .map(|(pages, line)| match put_in_order(&pages, &followers) {
+                Ok(sorted) => Ok(Update { pages, sorted }),
+                Err(_) => Err(ParseError::at(
+                    input,
+                    line,
+                    "pages that the rules can put in order",
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Manual { rules, updates })
+    }
+
+    fn part1(manual: &Self::Parsed) -> usize {
+        manual
+            .updates
+            .iter()
+            .filter(|update| update.is_sorted())
+            .map(|update| middle(&update.pages) as usize)
+            .sum()
+    }
+
+    fn part2(manual: &Self::Parsed) -> u64 {
+        manual
+            .updates
+            .iter()
+            .filter(|update| !update.is_sorted())
+            .map(|update| middle(&update.sorted) as u64)
+            .sum()
+    }
+}
+
+/// `size` is the number of updates. The rules order every pair of pages, up to 90 pages, and
+/// about half of the updates are already in order.
+impl Generate for Day05 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut order = (10..=99).collect::<Vec<u8>>();
+        rng.shuffle(&mut order);
+        order.truncate((size + 4).min(order.len()));
+        let mut rules = Vec::new();
+        for (i, before) in order.iter().enumerate() {
+            for after in &order[i + 1..] {
+                rules.push(format!("{before}|{after}"));
+            }
+        }
+        rng.shuffle(&mut rules);
+        let mut input = rules.join("\n");
+        input.push_str("\n\n");
+        for _ in 0..size {
+            let max_len = order.len().min(23);
+            let len = 2 * rng.below(max_len.div_ceil(2)) + 1;
+            let mut pages = order.clone();
+            rng.shuffle(&mut pages);
+            pages.truncate(len);
+            if rng.chance(0.5) {
+                pages.sort_by_key(|page| order.iter().position(|p| p == page));
             }
-        })
-        .sum();
-    count.to_string()
+            let pages = pages.iter().map(u8::to_string).collect::<Vec<_>>();
+            input.push_str(&pages.join(","));
+            input.push('\n');
+        }
+        input
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (input, (rules, updates)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let rules_map: HashMap<u8, Vec<u8>> =
-        rules
+impl Reference for Day05 {
+    /// An update is in order when no two of its pages break a rule.
+    fn reference_part1(manual: &Self::Parsed) -> usize {
+        manual
+            .updates
             .iter()
-            .copied()
-            .fold(HashMap::new(), |mut acc, (before, after)| {
-                acc.entry(after)
-                    .and_modify(|v| v.push(before))
-                    .or_insert(vec![before]);
-                acc
-            });
-    updates
-        .into_iter()
-        .filter(|pages| !is_correctly_ordered(pages, &rules_map))
-        .map(|mut pages| {
-            loop {
-                let mut made_change = false;
-                rules.iter().copied().for_each(|rule| {
-                    if let Some((before_index, after_index)) = find_rule(rule, &pages) {
-                        pages.swap(before_index, after_index);
-                        made_change = true;
+            .map(|update| &update.pages)
+            .filter(|pages| is_ordered(pages, &manual.rules))
+            .map(|pages| pages[pages.len() / 2] as usize)
+            .sum()
+    }
+
+    fn reference_part2(manual: &Self::Parsed) -> u64 {
+        manual
+            .updates
+            .iter()
+            .map(|update| &update.pages)
+            .filter(|pages| !is_ordered(pages, &manual.rules))
+            .map(|pages| {
+                let mut pages = pages.clone();
+                pages.sort_by(|&a, &b| {
+                    if manual.rules.contains(&(a, b)) {
+                        Ordering::Less
+                    } else if manual.rules.contains(&(b, a)) {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
                     }
                 });
-                if !made_change {
-                    break;
-                }
-            }
-            pages[pages.len() / 2] as u64
-        })
-        .sum::<u64>()
-        .to_string()
-}
-
-/// Returns Some(index of 'before', index of 'after') if rule is applicable, but not followed
-fn find_rule(rule: (u8, u8), pages: &[u8]) -> Option<(usize, usize)> {
-    let (before, after) = rule;
-    match (
-        pages.iter().enumerate().find(|&(_i, &n)| n == before),
-        pages.iter().enumerate().find(|&(_i, &n)| n == after),
-    ) {
-        (Some((before_i, _before_n)), Some((after_i, _after_n))) if before_i > after_i => {
-            Some((before_i, after_i))
-        }
-        _ => None,
+                pages[pages.len() / 2] as u64
+            })
+            .sum()
     }
 }
 
-fn is_correctly_ordered(pages: &Vec<u8>, rules: &HashMap<u8, Vec<u8>>) -> bool {
-    let mut must_not_encounter = Vec::new();
-    for &page in pages {
-        if must_not_encounter.contains(&page) {
-            return false;
-        }
-        if let Some(must_occur_before) = rules.get(&page) {
-            must_not_encounter.extend_from_slice(must_occur_before);
-        }
+fn is_ordered(pages: &[u8], rules: &Rules) -> bool {
+    (0..pages.len()).all(|i| (i + 1..pages.len()).all(|j| !rules.contains(&(pages[j], pages[i]))))
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day05>(input)
+}
+
+pub fn process_part2(input: &str) -> String {
+    common::solve_part2::<Day05>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day05>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day05>(input)
+}
+
+pub struct Manual {
+    rules: Rules,
+    updates: Vec<Update>,
+}
+
+pub struct Update {
+    pages: Vec<u8>,
+    /// The pages in the order of the rules. Pages that the rules leave free keep their place, so
+    /// an update that is already in order is unchanged.
+    sorted: Vec<u8>,
+}
+
+impl Update {
+    fn is_sorted(&self) -> bool {
+        self.pages == self.sorted
     }
-    true
+}
+
+fn middle(pages: &[u8]) -> u8 {
+    pages[pages.len() / 2]
+}
+
+/// Fails when the rules that apply to `pages` go round in a cycle.
+fn put_in_order(pages: &[u8], followers: &HashMap<u8, Vec<u8>>) -> Result<Vec<u8>, Cycle<u8>> {
+    graph::topological_sort(pages, |page| {
+        followers.get(page).into_iter().flatten().copied()
+    })
 }
 
 type Rules = Vec<(u8, u8)>;
@@ -104,42 +185,25 @@ fn parse_input(input: &str) -> IResult<&str, (Rules, Updates)> {
 }
 
 fn parse_rules(input: &str) -> IResult<&str, Vec<(u8, u8)>> {
-    let (input, rules) =
-        terminated(separated_list1(newline, parse_rule), pair(newline, newline))(input)?;
-    Ok((input, rules))
+    section(parse_rule)(input)
 }
 
 fn parse_rule(input: &str) -> IResult<&str, (u8, u8)> {
-    let (input, (a, _, b)) = tuple((nomu8, nomchar('|'), nomu8))(input)?;
+    let (input, (a, _, b)) = tuple((unsigned, nomchar('|'), unsigned))(input)?;
     Ok((input, (a, b)))
 }
 
 fn parse_pages(input: &str) -> IResult<&str, Vec<Vec<u8>>> {
-    let (input, pages) = separated_list1(newline, parse_page)(input)?;
-    Ok((input, pages))
+    lines(parse_page)(input)
 }
 
 fn parse_page(input: &str) -> IResult<&str, Vec<u8>> {
-    let (input, page) = separated_list1(nomchar(','), nomu8)(input)?;
-    Ok((input, page))
+    separated(nomchar(','), unsigned)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "143");
-    }
-
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "123");
-    }
+    common::day_tests!(Day05);
 }
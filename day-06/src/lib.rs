@@ -1,227 +1,340 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, iter};
 
-use indicatif::{ProgressBar, ProgressStyle};
+use common::{Generate, ParseError, Progress, Reference, Rng, Solution, SolveError};
+use grid::{Direction, Grid, Point};
+use visualize::{Cell, Frame, Rgb, Simulation, Visualize};
 
-use nom::{
-    character::complete::{newline, one_of},
-    multi::{many1, separated_list1},
-    IResult,
-};
+pub struct Day06;
 
-pub fn process_part1(input: &str) -> String {
-    let (input, (lab, mut guard)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let width = lab[0].len();
-    let height = lab.len();
-    let mut visited = vec![vec![false; width]; height];
-    loop {
-        visited[guard.y][guard.x] = true;
-        let (next_x, next_y) = match guard.direction {
-            Direction::Up => {
-                if guard.y > 0 {
-                    (guard.x, guard.y - 1)
-                } else {
-                    break;
-                }
-            }
-            Direction::Down => {
-                if guard.y < (height - 1) {
-                    (guard.x, guard.y + 1)
-                } else {
-                    break;
-                }
-            }
-            Direction::Left => {
-                if guard.x > 0 {
-                    (guard.x - 1, guard.y)
-                } else {
-                    break;
+impl Solution for Day06 {
+    type Parsed = (Lab, Guard);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let lab = Lab {
+            grid: parsing::grid(input, Location::parse)?,
+        };
+        let guard = parsing::find(input, '^')
+            .map(|(x, y)| Guard::new(x, y))
+            .ok_or_else(|| ParseError::at(input, &input[input.len()..], "a guard `^`"))?;
+        Ok((lab, guard))
+    }
+
+    fn part1((lab, guard): &Self::Parsed) -> usize {
+        let mut visited = Grid::filled(lab.grid.width(), lab.grid.height(), false);
+        for guard in lab.patrol(guard) {
+            visited[guard.pos] = true;
+        }
+        visited.cells().filter(|v| **v).count()
+    }
+
+    fn part2((lab, guard): &Self::Parsed) -> usize {
+        count_loop_obstructions(lab, guard, &*common::progress())
+    }
+}
+
+/// `size` is the side of the square lab. Labs where the guard would never leave are thrown away.
+impl Generate for Day06 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        loop {
+            let guard = (rng.below(size), rng.below(size));
+            let mut input = String::new();
+            for y in 0..size {
+                for x in 0..size {
+                    input.push(match (x, y) {
+                        pos if pos == guard => '^',
+                        _ if rng.chance(0.1) => '#',
+                        _ => '.',
+                    });
                 }
+                input.push('\n');
             }
-            Direction::Right => {
-                if guard.x < (width - 1) {
-                    (guard.x + 1, guard.y)
-                } else {
-                    break;
-                }
+            let (lab, guard) = Self::parse(&input).expect("generated lab should parse");
+            if !is_loop(&lab, &guard) {
+                return input;
             }
+        }
+    }
+}
+
+impl Reference for Day06 {
+    fn reference_part1((lab, guard): &Self::Parsed) -> usize {
+        walk(lab, guard).map_or(0, |visited| visited.len())
+    }
+
+    /// Tries an obstruction on every free position, not only those on the guard's path like the
+    /// solver, and tells a loop by the guard taking more steps than the lab has states instead of
+    /// by a repeated state.
+    fn reference_part2((lab, guard): &Self::Parsed) -> usize {
+        lab.grid
+            .positions()
+            .map(Point::from)
+            .filter(|&pos| lab.grid[pos] == Location::Path && pos != guard.pos)
+            .filter(|&pos| walk(&lab.with_obstruction(pos), guard).is_none())
+            .count()
+    }
+}
+
+/// The positions the guard visits before leaving `lab`, or `None` if they are still inside after
+/// more steps than the lab has states.
+fn walk(lab: &Lab, guard: &Guard) -> Option<HashSet<Point>> {
+    let mut guard = guard.clone();
+    let mut visited = HashSet::from([guard.pos]);
+    for _ in 0..=lab.states() {
+        let Some(next) = lab.step(&guard) else {
+            return Some(visited);
         };
-        match lab[next_y][next_x] {
-            Location::Path => {
-                guard.set_pos(next_x, next_y);
+        visited.insert(next.pos);
+        guard = next;
+    }
+    None
+}
+
+/// Both parts watch the guard's patrol.
+impl Visualize for Day06 {
+    fn simulation((lab, guard): &Self::Parsed, _part: u8) -> Box<dyn Simulation> {
+        Box::new(GuardWalk::new(lab.clone(), guard.clone()))
+    }
+}
+
+/// The guard's patrol, one step or turn at a time, until they leave the lab.
+pub struct GuardWalk {
+    lab: Lab,
+    guard: Guard,
+    visited: Grid<bool>,
+    steps: usize,
+    left: bool,
+}
+
+impl GuardWalk {
+    pub fn new(lab: Lab, guard: Guard) -> Self {
+        let mut visited = Grid::filled(lab.grid.width(), lab.grid.height(), false);
+        visited[guard.pos] = true;
+        Self {
+            lab,
+            guard,
+            visited,
+            steps: 0,
+            left: false,
+        }
+    }
+
+    /// How many distinct positions the guard has been on.
+    pub fn visited(&self) -> usize {
+        self.visited.cells().filter(|v| **v).count()
+    }
+}
+
+impl Simulation for GuardWalk {
+    fn frame(&self) -> Frame {
+        let mut frame = self.lab.grid.map(|location| match location {
+            Location::Path => Cell::new('.', Rgb::DIM),
+            Location::Obstruction => Cell::new('#', Rgb::WALL),
+        });
+        for (pos, _) in self.visited.iter().filter(|(_, v)| **v) {
+            frame[pos] = Cell::new('X', Rgb::HIGHLIGHT);
+        }
+        if !self.left {
+            frame[self.guard.pos] = Cell::new(self.guard.direction.arrow(), Rgb::ACTOR);
+        }
+        frame
+    }
+
+    fn step(&mut self) -> bool {
+        // a guard still inside after as many steps as there are states is walking in a loop
+        if self.left || self.steps > self.lab.states() {
+            return false;
+        }
+        self.steps += 1;
+        match self.lab.step(&self.guard) {
+            None => self.left = true,
+            Some(next) => {
+                self.visited[next.pos] = true;
+                self.guard = next;
             }
-            Location::Obstruction => guard.turn(),
         }
+        true
+    }
+
+    fn caption(&self) -> String {
+        format!("{} positions visited", self.visited())
     }
-    let count = visited.into_iter().flatten().filter(|v| *v).count();
-    count.to_string()
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day06>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let path_count = input.chars().filter(|&c| c == '.').count();
-    let (input, (original_lab, original_guard)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let width = original_lab[0].len();
-    let height = original_lab.len();
-
-    let bar = ProgressBar::new(path_count as u64);
-    bar.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] [{bar:50}] {pos}/{len}")
-            .unwrap()
-            .progress_chars("=> "),
-    );
-
-    let hypothetical_obstructions = (0..height)
-        .flat_map(|y| (0..width).map(move |x| (x, y)))
-        .filter(|&(x, y)| original_lab[y][x] == Location::Path)
-        .filter(|&(x, y)| original_guard.x != x || original_guard.y != y)
-        .filter(|&(x, y)| {
-            bar.inc(1);
-            let mut lab = original_lab.clone();
-            lab[y][x] = Location::Obstruction;
-            let mut visited = HashSet::new();
-            let mut guard = original_guard.clone();
-            loop {
-                if visited.contains(&guard) {
-                    break true;
-                }
-                visited.insert(guard.clone());
-                let (next_x, next_y) = match guard.direction {
-                    Direction::Up => {
-                        if guard.y > 0 {
-                            (guard.x, guard.y - 1)
-                        } else {
-                            break false;
-                        }
-                    }
-                    Direction::Down => {
-                        if guard.y < (height - 1) {
-                            (guard.x, guard.y + 1)
-                        } else {
-                            break false;
-                        }
-                    }
-                    Direction::Left => {
-                        if guard.x > 0 {
-                            (guard.x - 1, guard.y)
-                        } else {
-                            break false;
-                        }
-                    }
-                    Direction::Right => {
-                        if guard.x < (width - 1) {
-                            (guard.x + 1, guard.y)
-                        } else {
-                            break false;
-                        }
-                    }
-                };
-                match lab[next_y][next_x] {
-                    Location::Path => {
-                        guard.set_pos(next_x, next_y);
-                    }
-                    Location::Obstruction => guard.turn(),
-                }
-            }
-        })
-        .count();
-    bar.finish_with_message("Done!");
-    hypothetical_obstructions.to_string()
+    common::solve_part2::<Day06>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day06>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day06>(input)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Location {
+pub enum Location {
     Path,
     Obstruction,
 }
 
 impl Location {
-    fn parse(input: &str) -> IResult<&str, Self> {
-        match one_of(".#^")(input)? {
-            (input, '.') | (input, '^') => Ok((input, Self::Path)),
-            (input, '#') => Ok((input, Self::Obstruction)),
-            _ => unreachable!(),
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' | '^' => Some(Self::Path),
+            '#' => Some(Self::Obstruction),
+            _ => None,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Guard {
-    x: usize,
-    y: usize,
+pub struct Guard {
+    pos: Point,
     direction: Direction,
 }
 
 impl Guard {
     fn new(x: usize, y: usize) -> Self {
         Self {
-            x,
-            y,
+            pos: Point::new(x, y),
             direction: Direction::Up,
         }
     }
+}
 
-    fn set_pos(&mut self, x: usize, y: usize) {
-        self.x = x;
-        self.y = y;
+#[derive(Debug, Clone)]
+pub struct Lab {
+    grid: Grid<Location>,
+}
+
+impl Lab {
+    /// Where `guard` is after one more step or turn, or `None` once they have left the lab.
+    fn step(&self, guard: &Guard) -> Option<Guard> {
+        let next = self.grid.step(guard.pos, guard.direction)?;
+        Some(match self.grid[next] {
+            Location::Path => Guard {
+                pos: next,
+                direction: guard.direction,
+            },
+            Location::Obstruction => Guard {
+                pos: guard.pos,
+                direction: guard.direction.turn_right(),
+            },
+        })
     }
 
-    fn turn(&mut self) {
-        self.direction = match self.direction {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
+    /// The guard from `guard` on, until they leave the lab or have taken more steps than
+    /// [`Lab::states`], which only a guard walking in a loop does.
+    fn patrol<'a>(&'a self, guard: &Guard) -> impl Iterator<Item = Guard> + 'a {
+        iter::successors(Some(guard.clone()), |guard| self.step(guard)).take(self.states() + 1)
     }
-}
 
-type Lab = Vec<Vec<Location>>;
+    /// How many different positions and directions a guard can have in the lab.
+    fn states(&self) -> usize {
+        4 * self.grid.len()
+    }
 
-fn parse_input(input: &str) -> IResult<&str, (Lab, Guard)> {
-    let guard = parse_guard(input);
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, (lines, guard)))
+    fn with_obstruction(&self, pos: Point) -> Self {
+        let mut lab = self.clone();
+        lab.grid[pos] = Location::Obstruction;
+        lab
+    }
 }
 
-fn parse_line(input: &str) -> IResult<&str, Vec<Location>> {
-    let (input, row) = many1(Location::parse)(input)?;
-    Ok((input, row))
+/// How many paths would trap the guard in a loop if an obstruction were put there, reporting one
+/// step per candidate path.
+pub fn count_loop_obstructions(
+    original_lab: &Lab,
+    guard: &Guard,
+    progress: &dyn Progress,
+) -> usize {
+    // an obstruction off the guard's patrol never gets in their way, and none can be put where
+    // they start
+    let mut on_patrol = Grid::filled(original_lab.grid.width(), original_lab.grid.height(), false);
+    for guard in original_lab.patrol(guard) {
+        on_patrol[guard.pos] = true;
+    }
+    on_patrol[guard.pos] = false;
+    let candidates = on_patrol
+        .iter()
+        .filter(|(_, &candidate)| candidate)
+        .map(|(pos, _)| Point::from(pos))
+        .collect::<Vec<_>>();
+    progress.start("obstructions", candidates.len() as u64);
+    let loops = common::par_map(&candidates, |&pos| {
+        progress.advance(1);
+        is_loop(&original_lab.with_obstruction(pos), guard)
+    })
+    .into_iter()
+    .filter(|&is_loop| is_loop)
+    .count();
+    progress.finish();
+    loops
 }
 
-fn parse_guard(input: &str) -> Guard {
-    let byte_pos = input.find('^').expect("Cannot find guard in input!");
-    let line_length = input.find("\n").expect("Only one row in lab!");
-    let y = input[..byte_pos].chars().filter(|&c| c == '\n').count();
-    let x = (byte_pos - y) % line_length;
-    Guard::new(x, y)
+/// Whether the guard walks around `lab` forever instead of leaving it.
+fn is_loop(lab: &Lab, guard: &Guard) -> bool {
+    common::find_cycle(guard.clone(), |guard| lab.step(guard)).is_some()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{fs, sync::Mutex};
+
     use super::*;
-    use std::fs;
+
+    common::day_tests!(Day06, parallel);
+
+    /// Records the length of the last task and the steps reported so far.
+    #[derive(Default)]
+    struct Steps(Mutex<(u64, u64)>);
+
+    impl Progress for Steps {
+        fn start(&self, _label: &str, len: u64) {
+            *self.0.lock().unwrap() = (len, 0);
+        }
+
+        fn advance(&self, steps: u64) {
+            self.0.lock().unwrap().1 += steps;
+        }
+
+        fn finish(&self) {}
+    }
 
     #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "41");
+    fn reports_every_candidate() {
+        let input = fs::read_to_string("part1-example1.txt").unwrap();
+        let (lab, guard) = Day06::parse(&common::normalize(&input)).unwrap();
+        let steps = Steps::default();
+        assert_eq!(count_loop_obstructions(&lab, &guard, &steps), 6);
+        // the guard visits 41 positions, one of which is where they start
+        assert_eq!(*steps.0.lock().unwrap(), (40, 40));
     }
 
     #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "6");
+    fn watches_the_patrol() {
+        let input = fs::read_to_string("part1-example1.txt").unwrap();
+        let mut walk = visualize::simulate::<Day06>(&input, 1).unwrap();
+        let first = visualize::text(&walk.frame());
+        assert_eq!(first.trim_end(), input.trim_end());
+        assert!(visualize::skip(walk.as_mut(), 1000) < 1000);
+        let last = visualize::text(&walk.frame());
+        assert_eq!(last.matches('X').count(), 41);
+        assert_eq!(walk.caption(), "41 positions visited");
+    }
+
+    #[test]
+    fn missing_guard() {
+        let Err(SolveError::Parse(error)) = try_process_part1("#..\n...") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.expected, "a guard `^`");
     }
 }
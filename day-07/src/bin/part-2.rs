@@ -1,7 +1,10 @@
-use answer::process_part2;
-use std::fs;
+use day_07::{stream_part2, try_process_part2};
 
 fn main() {
-    let file = fs::read_to_string("./input.txt").unwrap();
-    println!("{}", process_part2(&file));
+    common::run_streamed_part(
+        2,
+        env!("CARGO_MANIFEST_DIR"),
+        try_process_part2,
+        stream_part2,
+    );
 }
@@ -1,110 +1,289 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::newline,
-    character::complete::{char as nomchar, u64 as nomu64},
-    multi::separated_list1,
-    IResult,
+use std::io::BufRead;
+
+use common::{
+    Generate, IResult, OrOverflow, Overflow, ParseError, Reference, Rng, Separator, Solution,
+    SolveError, Stream,
 };
 
+use nom::{bytes::complete::tag, character::complete::char as nomchar};
+use parsing::{lines, separated, unsigned};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Parsed = Vec<Equation>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(equations: &Self::Parsed) -> u64 {
+        Self::checked_part1(equations).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn part2(equations: &Self::Parsed) -> u64 {
+        Self::checked_part2(equations).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn checked_part1(equations: &Self::Parsed) -> Result<u64, Overflow> {
+        total(common::par_map(equations, Equation::test_value))
+    }
+
+    fn checked_part2(equations: &Self::Parsed) -> Result<u64, Overflow> {
+        let progress = common::progress();
+        progress.start("equations", equations.len() as u64);
+        let solvable = common::par_map(equations, |e| {
+            progress.advance(1);
+            e.test_value2()
+        });
+        progress.finish();
+        total(solvable)
+    }
+}
+
+impl Stream for Day07 {
+    const SEPARATOR: Separator = Separator::Line;
+
+    type Record = Equation;
+    /// The total calibration result so far.
+    type Tally1 = u64;
+    type Tally2 = u64;
+
+    fn parse_record(line: &str) -> Result<Equation, ParseError> {
+        common::finish(line, Equation::parse(line))
+    }
+
+    fn tally1(total: &mut u64, e: &Equation) -> Result<(), Overflow> {
+        if let Some(test_value) = e.test_value() {
+            *total = calibrate(*total, test_value)?;
+        }
+        Ok(())
+    }
+
+    fn tally2(total: &mut u64, e: &Equation) -> Result<(), Overflow> {
+        if let Some(test_value) = e.test_value2() {
+            *total = calibrate(*total, test_value)?;
+        }
+        Ok(())
+    }
+
+    fn answer1(total: u64) -> Result<u64, Overflow> {
+        Ok(total)
+    }
+
+    fn answer2(total: u64) -> Result<u64, Overflow> {
+        Ok(total)
+    }
+}
+
+/// `size` is the number of equations. Most can be solved with some mix of operators; the
+/// numbers are kept short enough that no mix overflows a `u64`.
+impl Generate for Day07 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size {
+            let count = rng.range(2..=12);
+            // concatenating everything gives the largest result, so its digits must fit
+            let max_digits = (18 / count).min(3);
+            let numbers = (0..count)
+                .map(|_| {
+                    let digits = rng.range(1..=max_digits) as u32;
+                    rng.range(1..=10_i64.pow(digits) - 1) as u64
+                })
+                .collect::<Vec<_>>();
+            let mut target = numbers[1..]
+                .iter()
+                .fold(numbers[0], |acc, &n| match rng.below(3) {
+                    0 => acc + n,
+                    1 => acc * n,
+                    _ => concatenate(acc, n).expect("short numbers concatenate to a u64"),
+                });
+            if rng.chance(0.3) {
+                target += rng.range(1..=9) as u64;
+            }
+            let numbers = numbers.iter().map(u64::to_string).collect::<Vec<_>>();
+            input.push_str(&format!("{target}: {}\n", numbers.join(" ")));
+        }
+        input
+    }
+}
+
+impl Reference for Day07 {
+    fn reference_part1(equations: &Self::Parsed) -> u64 {
+        equations
+            .iter()
+            .filter(|e| any_combination(e, 2))
+            .map(|e| e.lhs)
+            .sum()
+    }
+
+    fn reference_part2(equations: &Self::Parsed) -> u64 {
+        equations
+            .iter()
+            .filter(|e| any_combination(e, 3))
+            .map(|e| e.lhs)
+            .sum()
+    }
+}
+
+/// Tries every combination of the first `operators` of `+`, `*` and `||`, counting through them
+/// as the digits of a number in base `operators`.
+fn any_combination(equation: &Equation, operators: u32) -> bool {
+    let gaps = equation.rhs.len() as u32 - 1;
+    (0..operators.pow(gaps)).any(|mut combination| {
+        let mut result = equation.rhs[0];
+        for &n in &equation.rhs[1..] {
+            result = match combination % operators {
+                0 => result + n,
+                1 => result * n,
+                _ => format!("{result}{n}").parse().unwrap(),
+            };
+            combination /= operators;
+        }
+        result == equation.lhs
+    })
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, equations) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    equations
-        .into_iter()
-        .filter(|e| {
-            let target = e.lhs;
-            let current = e.rhs[0];
-            evaluate(target, current, &e.rhs[1..])
-        })
-        .map(|e| e.lhs)
-        .sum::<u64>()
-        .to_string()
+    common::solve_part1::<Day07>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, equations) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    equations
-        .into_iter()
-        .filter(|e| {
-            let target = e.lhs;
-            let current = e.rhs[0];
-            evaluate2(target, current, &e.rhs[1..])
-        })
-        .map(|e| e.lhs)
-        .sum::<u64>()
-        .to_string()
+    common::solve_part2::<Day07>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day07>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day07>(input)
+}
+
+pub fn stream_part1(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part1::<Day07>(input)
+}
+
+pub fn stream_part2(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part2::<Day07>(input)
 }
 
 #[derive(Debug)]
-struct Equation {
+pub struct Equation {
     lhs: u64,
     rhs: Vec<u64>,
 }
 
 impl Equation {
     fn parse(input: &str) -> IResult<&str, Self> {
-        let (input, lhs) = nomu64(input)?;
+        let (input, lhs) = unsigned(input)?;
         let (input, _) = tag(": ")(input)?;
-        let (input, rhs) = separated_list1(nomchar(' '), nomu64)(input)?;
+        let (input, rhs) = separated(nomchar(' '), unsigned)(input)?;
         Ok((input, Self { lhs, rhs }))
     }
-}
 
-fn evaluate(target: u64, current: u64, tail: &[u64]) -> bool {
-    if tail.is_empty() {
-        return target == current;
+    /// The test value, if `+` and `*` can make the equation true.
+    fn test_value(&self) -> Option<u64> {
+        evaluate(self.lhs, Some(self.rhs[0]), &self.rhs[1..]).then_some(self.lhs)
     }
-    let next_number = tail[0];
-    let plus = current + next_number;
-    let mult = current * next_number;
-    evaluate(target, plus, &tail[1..]) || evaluate(target, mult, &tail[1..])
+
+    /// The test value, if `+`, `*` and `||` can make the equation true.
+    fn test_value2(&self) -> Option<u64> {
+        evaluate2(self.lhs, Some(self.rhs[0]), &self.rhs[1..]).then_some(self.lhs)
+    }
+}
+
+/// The total calibration result of the equations that can be made true.
+fn total(solvable: Vec<Option<u64>>) -> Result<u64, Overflow> {
+    solvable.into_iter().flatten().try_fold(0, calibrate)
 }
 
-fn evaluate2(target: u64, current: u64, tail: &[u64]) -> bool {
-    if tail.is_empty() {
-        return target == current;
+/// Adds the test value of a true equation to the total calibration result.
+fn calibrate(total: u64, lhs: u64) -> Result<u64, Overflow> {
+    total
+        .checked_add(lhs)
+        .or_overflow("the total calibration result")
+}
+
+/// An intermediate result of an equation, `None` once it no longer fits in a `u64`. Such a
+/// result can still come back by being multiplied by zero.
+type Partial = Option<u64>;
+
+fn add(current: Partial, next: u64) -> Partial {
+    current?.checked_add(next)
+}
+
+fn multiply(current: Partial, next: u64) -> Partial {
+    match next {
+        0 => Some(0),
+        next => current?.checked_mul(next),
     }
-    let next_number = tail[0];
-    let plus = current + next_number;
-    let mult = current * next_number;
-    let conc = concatenate(current, next_number);
-    evaluate2(target, plus, &tail[1..])
-        || evaluate2(target, mult, &tail[1..])
-        || evaluate2(target, conc, &tail[1..])
 }
 
-fn concatenate(a: u64, b: u64) -> u64 {
-    let zeroes = b.ilog10() + 1;
-    a * 10_u64.pow(zeroes) + b
+fn evaluate(target: u64, current: Partial, tail: &[u64]) -> bool {
+    let Some((&next_number, tail)) = tail.split_first() else {
+        return current == Some(target);
+    };
+    let plus = add(current, next_number);
+    let mult = multiply(current, next_number);
+    evaluate(target, plus, tail) || evaluate(target, mult, tail)
+}
+
+fn evaluate2(target: u64, current: Partial, tail: &[u64]) -> bool {
+    let Some((&next_number, tail)) = tail.split_first() else {
+        return current == Some(target);
+    };
+    let plus = add(current, next_number);
+    let mult = multiply(current, next_number);
+    let conc = current.and_then(|current| concatenate(current, next_number));
+    evaluate2(target, plus, tail) || evaluate2(target, mult, tail) || evaluate2(target, conc, tail)
+}
+
+/// The digits of `a` followed by those of `b`, if that still fits in a `u64`.
+fn concatenate(a: u64, b: u64) -> Option<u64> {
+    let zeroes = b.checked_ilog10().unwrap_or(0) + 1;
+    a.checked_mul(10_u64.checked_pow(zeroes)?)?.checked_add(b)
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Equation>> {
-    let (input, lines) = separated_list1(newline, Equation::parse)(input)?;
-    Ok((input, lines))
+    lines(Equation::parse)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "3749");
-    }
+    common::day_tests!(Day07, stream, parallel);
 
     #[test]
     fn test_concatenate() {
-        assert_eq!(concatenate(1234, 5678), 12345678)
+        assert_eq!(concatenate(1234, 5678), Some(12345678));
+        assert_eq!(concatenate(12, 0), Some(120));
+        assert_eq!(concatenate(u64::MAX / 10, 5), Some(u64::MAX));
+        assert_eq!(concatenate(u64::MAX / 10, 6), None);
+        assert_eq!(concatenate(1, u64::MAX), None);
     }
 
     #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "11387");
+    fn overflowing_equations() {
+        let max = u64::MAX;
+        assert_eq!(
+            process_part1(&format!("{max}: {} 1", max - 1)),
+            max.to_string()
+        );
+        // the product overflows, but multiplying it by zero brings it back
+        assert_eq!(process_part1(&format!("5: {max} {max} 0 5")), "5");
+        assert_eq!(process_part1(&format!("5: {max} {max} 5")), "0");
+        assert_eq!(
+            process_part2(&format!("{max}: {} 5", max / 10)),
+            max.to_string()
+        );
+        let error = try_process_part1(&format!("{max}: {max}\n1: 1")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "arithmetic overflow computing the total calibration result"
+        );
     }
 }
@@ -0,0 +1,3 @@
+fn main() {
+    common::write_example_tests();
+}
@@ -1,151 +1,175 @@
 use std::collections::{HashMap, HashSet};
 
+use common::{Generate, ParseError, Reference, Rng, Solution, SolveError};
+use grid::{Point, Vector};
 use itertools::Itertools;
 
-pub fn process_part1(input: &str) -> String {
-    let width = input.lines().next().expect("Invalid input!").len() as u8;
-    let height = input.lines().count() as u8;
-    let map = parse_input(input);
-    let anti_nodes = map
-        .into_iter()
-        .flat_map(|(_k, v)| {
-            v.into_iter().combinations(2).flat_map(|c| {
-                let ((mut x_a, mut y_a), (mut x_b, mut y_b)) = (c[0], c[1]);
-                // make a be the leftmost point:
-                if x_a > x_b {
-                    std::mem::swap(&mut x_a, &mut x_b);
-                    std::mem::swap(&mut y_a, &mut y_b);
-                }
-                let dx = x_a.abs_diff(x_b);
-                let dy = y_a.abs_diff(y_b);
-                [
-                    x_a.min(x_b).checked_sub(dx).and_then(|left_x| {
-                        if y_a < y_b {
-                            y_a.checked_sub(dy).map(|left_y| (left_x, left_y))
-                        } else {
-                            Some((left_x, y_a + dy))
-                        }
-                    }),
-                    {
-                        let right_x = x_a.max(x_b) + dx;
-                        if y_a < y_b {
-                            Some((right_x, y_b + dy))
-                        } else {
-                            y_b.checked_sub(dy).map(|right_y| (right_x, right_y))
-                        }
-                    },
-                ]
-                .into_iter()
-                .flatten()
-            })
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Parsed = Antennas;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        // every cell is empty or an antenna of some frequency
+        let grid = parsing::grid(input, |c| match c {
+            '.' => Some(None),
+            c if c.is_ascii_alphanumeric() => Some(Some(c)),
+            _ => None,
+        })?;
+        let mut map = HashMap::<char, Vec<Point>>::new();
+        for ((x, y), &frequency) in grid.iter() {
+            if let Some(frequency) = frequency {
+                map.entry(frequency).or_default().push(Point::new(x, y));
+            }
+        }
+        Ok(Antennas {
+            width: grid.width(),
+            height: grid.height(),
+            map,
         })
-        .filter(|&(x, y)| x < width && y < height)
-        .collect::<HashSet<_>>();
-    anti_nodes.len().to_string()
-}
+    }
 
-pub fn process_part2(input: &str) -> String {
-    let width = input.lines().next().expect("Invalid input!").len() as u8;
-    let height = input.lines().count() as u8;
-    let map = parse_input(input);
-    let anti_nodes =
-        map.into_iter()
-            .flat_map(|(_k, v)| {
-                v.into_iter().combinations(2).flat_map(|c| {
-                    let ((mut x_a, mut y_a), (mut x_b, mut y_b)) = (c[0], c[1]);
-                    // make a be the leftmost point:
-                    if x_a > x_b {
-                        std::mem::swap(&mut x_a, &mut x_b);
-                        std::mem::swap(&mut y_a, &mut y_b);
-                    }
-                    let dx = x_a.abs_diff(x_b);
-                    let dy = y_a.abs_diff(y_b);
-                    let start_x = x_a % dx;
-                    let iter: Box<dyn Iterator<Item = (u8, u8)>> = if dx == 0 {
-                        let start_y = y_a.min(y_b) % dy;
-                        Box::new(
-                            (start_y..)
-                                .step_by(dy as usize)
-                                .take_while(|y| y < &height)
-                                .map(move |y| (x_a, y)),
-                        )
-                    } else {
-                        match y_a.cmp(&y_b) {
-                            std::cmp::Ordering::Less => Box::new(
-                                (x_b..)
-                                    .step_by(dx as usize)
-                                    .take_while(|x| x < &width)
-                                    .zip((y_b..).step_by(dy as usize).take_while(|y| y < &height))
-                                    .chain((0u8..).map_while(move |m| {
-                                        y_a.checked_sub(m * dy)
-                                            .and_then(|y| x_a.checked_sub(m * dx).map(|x| (x, y)))
-                                    })),
-                            ),
-                            std::cmp::Ordering::Equal => Box::new(
-                                (start_x..)
-                                    .step_by(dx as usize)
-                                    .take_while(|x| x < &width)
-                                    .map(move |x| (x, y_a)),
-                            ),
-                            std::cmp::Ordering::Greater => Box::new(
-                                (x_b..)
-                                    .step_by(dx as usize)
-                                    .take_while(|x| x < &width)
-                                    .zip((0..).map_while(move |m| y_b.checked_sub(m * dy)))
-                                    .chain((0..).map_while(move |m| x_a.checked_sub(m * dx)).zip(
-                                        (y_a..).step_by(dy as usize).take_while(|y| y < &height),
-                                    )),
-                            ),
-                        }
+    fn part1(antennas: &Self::Parsed) -> usize {
+        let (width, height) = (antennas.width, antennas.height);
+        let anti_nodes = antennas
+            .map
+            .values()
+            .flat_map(|v| {
+                v.iter().tuple_combinations().flat_map(|(&a, &b)| {
+                    let step = b - a;
+                    [
+                        a.offset_within(-step, width, height),
+                        b.offset_within(step, width, height),
+                    ]
+                    .into_iter()
+                    .flatten()
+                })
+            })
+            .collect::<HashSet<_>>();
+        anti_nodes.len()
+    }
+
+    fn part2(antennas: &Self::Parsed) -> usize {
+        let (width, height) = (antennas.width, antennas.height);
+        let line = move |start: Point, step: Vector| {
+            std::iter::successors(Some(start), move |p| p.offset_within(step, width, height))
+        };
+        let anti_nodes = antennas
+            .map
+            .values()
+            .flat_map(|v| {
+                v.iter().tuple_combinations().flat_map(move |(&a, &b)| {
+                    // the smallest step along the line, so no grid position in between is missed
+                    let step = b - a;
+                    let divisor = common::gcd(step.dx, step.dy);
+                    let step = Vector {
+                        dx: step.dx / divisor,
+                        dy: step.dy / divisor,
                     };
-                    iter
+                    line(a, -step).chain(line(a, step))
                 })
             })
             .collect::<HashSet<_>>();
-    anti_nodes.len().to_string()
+        anti_nodes.len()
+    }
 }
 
-fn parse_input(input: &str) -> HashMap<char, Vec<(u8, u8)>> {
-    let mut y = 0;
-    let mut map = HashMap::new();
-    input.lines().for_each(|line| {
-        line.char_indices()
-            .filter(|(_, c)| c != &'.')
-            .for_each(|(x, c)| {
-                map.entry(c)
-                    .and_modify(|v: &mut Vec<(u8, u8)>| {
-                        v.push((x as u8, y));
-                    })
-                    .or_insert_with(|| vec![(x as u8, y)]);
-            });
-        y += 1;
-    });
-    map
+/// `size` is the side of the square map; bigger maps also use more frequencies.
+impl Generate for Day08 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const FREQUENCIES: &[u8] =
+            b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let frequencies = &FREQUENCIES[..(size / 4 + 1).min(FREQUENCIES.len())];
+        let mut input = String::new();
+        for _ in 0..size {
+            input.extend((0..size).map(|_| {
+                if rng.chance(0.08) {
+                    *rng.choose(frequencies) as char
+                } else {
+                    '.'
+                }
+            }));
+            input.push('\n');
+        }
+        input
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
+impl Reference for Day08 {
+    /// Checks every position against every pair of antennas of the same frequency.
+    fn reference_part1(antennas: &Self::Parsed) -> usize {
+        count_anti_nodes(antennas, |p, a, b| {
+            p - a == (b - a) * 2 || p - b == (a - b) * 2
+        })
+    }
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "14");
+    fn reference_part2(antennas: &Self::Parsed) -> usize {
+        count_anti_nodes(antennas, |p, a, b| {
+            let (ap, ab) = (p - a, b - a);
+            ap.dx * ab.dy == ap.dy * ab.dx
+        })
     }
+}
 
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part2-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "9");
+fn count_anti_nodes(
+    antennas: &Antennas,
+    is_anti_node: impl Fn(Point, Point, Point) -> bool,
+) -> usize {
+    let mut count = 0;
+    for y in 0..antennas.height {
+        for x in 0..antennas.width {
+            let p = Point::new(x, y);
+            count += usize::from(antennas.map.values().any(|v| {
+                v.iter()
+                    .tuple_combinations()
+                    .any(|(&a, &b)| is_anti_node(p, a, b))
+            }));
+        }
     }
+    count
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day08>(input)
+}
+
+pub fn process_part2(input: &str) -> String {
+    common::solve_part2::<Day08>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day08>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day08>(input)
+}
+
+pub struct Antennas {
+    width: usize,
+    height: usize,
+    map: HashMap<char, Vec<Point>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    common::day_tests!(Day08);
 
     #[test]
-    fn part2_2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "34");
+    fn invalid_maps() {
+        let Err(SolveError::Parse(error)) = try_process_part2("...\n.....a\n....a.") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "a row of width 3");
+        let Err(SolveError::Parse(error)) = try_process_part1("..a\n.#.") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "a grid cell");
     }
 }
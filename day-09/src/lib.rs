@@ -1,53 +1,150 @@
-pub fn process_part1(input: &str) -> String {
-    let (mut disk, _, _) = parse_input(input);
-    let mut i = 0;
-    loop {
-        if i >= disk.len() {
-            break;
+use common::{Generate, ParseError, Reference, Rng, Solution, SolveError};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Parsed = DiskMap;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1((disk, _, _): &Self::Parsed) -> usize {
+        let mut disk = disk.clone();
+        let mut i = 0;
+        loop {
+            if i >= disk.len() {
+                break;
+            }
+            if disk[i].is_empty() {
+                disk.swap_remove(i);
+            } else {
+                i += 1;
+            }
         }
-        if disk[i].is_empty() {
-            disk.swap_remove(i);
-        } else {
-            i += 1;
+        disk.iter()
+            .enumerate()
+            .map(|(i, d)| match d {
+                DiskSpace::Empty => panic!("No space should be left on disk at this point"),
+                DiskSpace::File(ref id) => id * i,
+            })
+            .sum::<usize>()
+    }
+
+    fn part2((_disk, space, files): &Self::Parsed) -> usize {
+        let mut space = space.clone();
+        let mut files = files.clone();
+        files.iter_mut().rev().for_each(|f| {
+            if let Some(e) = space
+                .iter_mut()
+                .find(|e| e.len >= f.len && e.start < f.start)
+            {
+                f.start = e.start;
+                e.start += f.len;
+                e.len -= f.len;
+            }
+        });
+        files
+            .into_iter()
+            .enumerate()
+            .flat_map(|(id, f)| (f.start..).take(f.len).map(move |i| i * id))
+            .sum::<usize>()
+    }
+}
+
+/// `size` is the number of files on the disk.
+impl Generate for Day09 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for file in 0..size {
+            if file > 0 {
+                input.push_str(&rng.range(0..=9).to_string());
+            }
+            input.push_str(&rng.range(1..=9).to_string());
         }
+        input.push('\n');
+        input
     }
-    let checksum = disk
+}
+
+impl Reference for Day09 {
+    /// Moves the last file block into the first free block, one block at a time.
+    fn reference_part1((disk, _, _): &Self::Parsed) -> usize {
+        let mut blocks = blocks(disk);
+        while let Some(free) = blocks.iter().position(Option::is_none) {
+            let last = blocks.pop().unwrap();
+            if free < blocks.len() {
+                blocks[free] = last;
+            }
+        }
+        checksum(&blocks)
+    }
+
+    /// Moves whole files, highest ID first, into the first run of free blocks to their left that
+    /// fits them.
+    fn reference_part2((disk, _, files): &Self::Parsed) -> usize {
+        let mut blocks = blocks(disk);
+        for id in (0..files.len()).rev() {
+            let start = blocks.iter().position(|&b| b == Some(id)).unwrap();
+            let len = blocks[start..]
+                .iter()
+                .take_while(|&&b| b == Some(id))
+                .count();
+            let target = (0..start).find(|&i| blocks[i..i + len].iter().all(Option::is_none));
+            if let Some(target) = target {
+                blocks[target..target + len].fill(Some(id));
+                blocks[start..start + len].fill(None);
+            }
+        }
+        checksum(&blocks)
+    }
+}
+
+fn blocks(disk: &[DiskSpace]) -> Vec<Option<usize>> {
+    disk.iter()
+        .map(|space| match space {
+            DiskSpace::Empty => None,
+            DiskSpace::File(id) => Some(*id),
+        })
+        .collect()
+}
+
+fn checksum(blocks: &[Option<usize>]) -> usize {
+    blocks
         .iter()
         .enumerate()
-        .map(|(i, d)| match d {
-            DiskSpace::Empty => panic!("No space should be left on disk at this point"),
-            DiskSpace::File(ref id) => id * i,
-        })
-        .sum::<usize>();
-    checksum.to_string()
+        .map(|(i, block)| i * block.unwrap_or(0))
+        .sum()
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day09>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (_disk, mut space, mut files) = parse_input(input);
-    files.iter_mut().rev().for_each(|f| {
-        if let Some(e) = space
-            .iter_mut()
-            .find(|e| e.len >= f.len && e.start < f.start)
-        {
-            f.start = e.start;
-            e.start += f.len;
-            e.len -= f.len;
-        }
-    });
-    let checksum = files
-        .into_iter()
-        .enumerate()
-        .flat_map(|(id, f)| (f.start..).take(f.len).map(move |i| i * id))
-        .sum::<usize>();
-    checksum.to_string()
+    common::solve_part2::<Day09>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day09>(input)
 }
 
-fn parse_input(input: &str) -> (Vec<DiskSpace>, Vec<EmptySpace>, Vec<FileSpace>) {
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day09>(input)
+}
+
+pub type DiskMap = (Vec<DiskSpace>, Vec<EmptySpace>, Vec<FileSpace>);
+
+fn parse_input(input: &str) -> Result<DiskMap, ParseError> {
     let mut disk = Vec::new();
     let mut empty_space = Vec::new();
     let mut files = Vec::new();
-    input.char_indices().for_each(|(i, n)| {
-        let n = n.to_digit(10).expect("Invalid digit!") as usize;
+    for (i, n) in input.char_indices() {
+        let n = n
+            .to_digit(10)
+            .ok_or_else(|| ParseError::at(input, &input[i..], "a digit"))? as usize;
         match i % 2 {
             0 => {
                 files.push(FileSpace {
@@ -65,13 +162,19 @@ fn parse_input(input: &str) -> (Vec<DiskSpace>, Vec<EmptySpace>, Vec<FileSpace>)
             }
             _ => unreachable!(),
         }
-    });
-    assert!(disk.last().expect("Invalid disk!").is_file());
-    (disk, empty_space, files)
+    }
+    if !disk.last().is_some_and(DiskSpace::is_file) {
+        return Err(ParseError::at(
+            input,
+            &input[input.len()..],
+            "a disk map ending in a file",
+        ));
+    }
+    Ok((disk, empty_space, files))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum DiskSpace {
+pub enum DiskSpace {
     Empty,
     File(usize),
 }
@@ -92,12 +195,14 @@ impl DiskSpace {
     }
 }
 
-struct EmptySpace {
+#[derive(Debug, Clone)]
+pub struct EmptySpace {
     start: usize,
     len: usize,
 }
 
-struct FileSpace {
+#[derive(Debug, Clone)]
+pub struct FileSpace {
     start: usize,
     len: usize,
 }
@@ -105,19 +210,6 @@ struct FileSpace {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "1928");
-    }
-
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "2858");
-    }
+    common::day_tests!(Day09);
 }
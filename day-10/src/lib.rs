@@ -1,109 +1,133 @@
 use std::collections::HashSet;
 
-pub fn process_part1(input: &str) -> String {
-    let map = parse_input(input);
-    let width = map[0].len();
-    let height = map.len();
-    let mut stack = Vec::new();
-    let mut trailhead = HashSet::new();
-    let sum = map
-        .iter()
-        .enumerate()
-        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, h)| (x, y, *h)))
-        .filter(|(_x, _y, h)| h == &0)
-        .map(|(x, y, h)| {
-            stack.push((x, y, h));
-            while let Some((x, y, h)) = stack.pop() {
-                if h == 9 {
-                    trailhead.insert((x, y));
-                    continue;
-                }
-                if x > 0 && map[y][x - 1] == h + 1 {
-                    stack.push((x - 1, y, h + 1));
-                }
-                if x < (width - 1) && map[y][x + 1] == h + 1 {
-                    stack.push((x + 1, y, h + 1));
-                }
-                if y > 0 && map[y - 1][x] == h + 1 {
-                    stack.push((x, y - 1, h + 1));
-                }
-                if y < (height - 1) && map[y + 1][x] == h + 1 {
-                    stack.push((x, y + 1, h + 1));
-                }
+use common::{Generate, ParseError, Reference, Rng, Solution, SolveError};
+use grid::{Direction, Grid, Point};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(map: &Self::Parsed) -> usize {
+        trailheads(map)
+            .map(|pos| {
+                graph::dfs(pos, |&pos| climb(map, pos))
+                    .filter(|&pos| map[pos] == 9)
+                    .count()
+            })
+            .sum::<usize>()
+    }
+
+    fn part2(map: &Self::Parsed) -> usize {
+        trailheads(map)
+            .map(|pos| graph::count_paths(pos, |&pos| climb(map, pos), |&pos| map[pos] == 9))
+            .sum::<usize>()
+    }
+}
+
+fn trailheads(map: &Grid<u8>) -> impl Iterator<Item = (usize, usize)> + '_ {
+    map.iter().filter(|(_pos, h)| **h == 0).map(|(pos, _h)| pos)
+}
+
+/// The neighbours of `pos` one step higher up.
+fn climb(map: &Grid<u8>, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    map.neighbours4(pos)
+        .filter(move |&next| map[next] == map[pos] + 1)
+}
+
+/// `size` is the side of the square map. Heights are random apart from `size` hiking trails
+/// carved into them.
+impl Generate for Day10 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut map = Grid::from_vec(
+            size,
+            size,
+            (0..size * size).map(|_| rng.below(10) as u8).collect(),
+        );
+        for _ in 0..size {
+            let mut pos = Some(Point::new(rng.below(size), rng.below(size)));
+            let mut direction = *rng.choose(&Direction::ALL);
+            for height in 0..10 {
+                let Some(current) = pos else {
+                    break;
+                };
+                map[current] = height;
+                // never turn back onto the trail just carved
+                direction =
+                    *rng.choose(&[direction.turn_left(), direction, direction.turn_right()]);
+                pos = map.step(current, direction);
             }
-            let score = trailhead.len();
-            trailhead.clear();
-            score
-        })
-        .sum::<usize>();
-    sum.to_string()
+        }
+        format!("{map}\n")
+    }
+}
+
+impl Reference for Day10 {
+    fn reference_part1(map: &Self::Parsed) -> usize {
+        map.positions()
+            .filter(|&pos| map[pos] == 0)
+            .map(|pos| peaks(map, pos).len())
+            .sum()
+    }
+
+    fn reference_part2(map: &Self::Parsed) -> usize {
+        map.positions()
+            .filter(|&pos| map[pos] == 0)
+            .map(|pos| trails(map, pos))
+            .sum()
+    }
+}
+
+/// The height 9 positions reachable from `pos` by climbing one step at a time.
+fn peaks(map: &Grid<u8>, pos: (usize, usize)) -> HashSet<(usize, usize)> {
+    if map[pos] == 9 {
+        return HashSet::from([pos]);
+    }
+    map.neighbours4(pos)
+        .filter(|&next| map[next] == map[pos] + 1)
+        .flat_map(|next| peaks(map, next))
+        .collect()
+}
+
+fn trails(map: &Grid<u8>, pos: (usize, usize)) -> usize {
+    if map[pos] == 9 {
+        return 1;
+    }
+    map.neighbours4(pos)
+        .filter(|&next| map[next] == map[pos] + 1)
+        .map(|next| trails(map, next))
+        .sum()
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day10>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let map = parse_input(input);
-    let width = map[0].len();
-    let height = map.len();
-    let mut stack = Vec::new();
-    let sum = map
-        .iter()
-        .enumerate()
-        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, h)| (x, y, *h)))
-        .filter(|(_x, _y, h)| h == &0)
-        .map(|(x, y, h)| {
-            stack.push((x, y, h));
-            let mut rating = 0;
-            while let Some((x, y, h)) = stack.pop() {
-                if h == 9 {
-                    rating += 1;
-                    continue;
-                }
-                if x > 0 && map[y][x - 1] == h + 1 {
-                    stack.push((x - 1, y, h + 1));
-                }
-                if x < (width - 1) && map[y][x + 1] == h + 1 {
-                    stack.push((x + 1, y, h + 1));
-                }
-                if y > 0 && map[y - 1][x] == h + 1 {
-                    stack.push((x, y - 1, h + 1));
-                }
-                if y < (height - 1) && map[y + 1][x] == h + 1 {
-                    stack.push((x, y + 1, h + 1));
-                }
-            }
-            rating
-        })
-        .sum::<usize>();
-    sum.to_string()
+    common::solve_part2::<Day10>(input)
 }
 
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    let mut v = Vec::new();
-    input.lines().for_each(|row| {
-        v.push(
-            row.chars()
-                .map(|c| c.to_digit(10).expect("invalid digit in input") as u8)
-                .collect(),
-        )
-    });
-    v
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day10>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day10>(input)
+}
+
+fn parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    parsing::grid(input, |c| c.to_digit(10).map(|h| h as u8))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "36");
-    }
 
-    #[test]
-    fn part2() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "81");
-    }
+    common::day_tests!(Day10);
 }
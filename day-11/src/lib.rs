@@ -1,37 +1,140 @@
 use std::collections::HashMap;
 
-use nom::{
-    character::complete::space1, character::complete::u64 as nomu64, multi::separated_list1,
-    IResult,
+use common::{
+    Counter, Generate, IResult, Overflow, ParseError, Reference, Rng, Solution, SolveError,
 };
 
+use nom::combinator::cut;
+use parsing::{spaced, unsigned};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Vec<u64>;
+    type Answer1 = usize;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(stones: &Self::Parsed) -> usize {
+        let mut stones = stones
+            .iter()
+            .map(|&stone| u128::from(stone))
+            .collect::<Vec<_>>();
+        let progress = common::progress();
+        progress.start("blinks", 25);
+        (0..25).for_each(|_| {
+            stones = stones.iter().copied().flat_map(rules).collect();
+            progress.advance(1);
+        });
+        progress.finish();
+        stones.len()
+    }
+
+    fn part2(stones: &Self::Parsed) -> u64 {
+        Self::checked_part2(stones).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn checked_part2(stones: &Self::Parsed) -> Result<u64, Overflow> {
+        // the same number may be engraved on several stones
+        let stones = stones
+            .iter()
+            .map(|&stone| u128::from(stone))
+            .collect::<Counter<_>>();
+        let progress = common::progress();
+        progress.start("blinks", 75);
+        let blinked = (0..75).try_fold(stones, |stones, _| {
+            let stones = stones.map_keys(|&stone| rules(stone).map(|new_stone| (new_stone, 1)));
+            progress.advance(1);
+            stones
+        });
+        progress.finish();
+        blinked?.total()
+    }
+}
+
+/// `size` is the number of stones.
+impl Generate for Day11 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let stones = (0..size)
+            .map(|_| {
+                let digits = rng.range(1..=7) as u32;
+                rng.range(0..=10_i64.pow(digits) - 1).to_string()
+            })
+            .collect::<Vec<_>>();
+        format!("{}\n", stones.join(" "))
+    }
+}
+
+impl Reference for Day11 {
+    fn reference_part1(stones: &Self::Parsed) -> usize {
+        let mut seen = HashMap::new();
+        let count = stones
+            .iter()
+            .map(|&stone| count_stones(stone.into(), 25, &mut seen));
+        count.sum::<u64>() as usize
+    }
+
+    fn reference_part2(stones: &Self::Parsed) -> u64 {
+        let mut seen = HashMap::new();
+        stones
+            .iter()
+            .map(|&stone| count_stones(stone.into(), 75, &mut seen))
+            .sum()
+    }
+}
+
+/// The number of stones `stone` becomes after `blinks` blinks, splitting numbers as strings and
+/// remembering the count for every stone and number of blinks.
+fn count_stones(stone: u128, blinks: u32, seen: &mut HashMap<(u128, u32), u64>) -> u64 {
+    if blinks == 0 {
+        return 1;
+    }
+    if let Some(&count) = seen.get(&(stone, blinks)) {
+        return count;
+    }
+    let digits = stone.to_string();
+    let count = if stone == 0 {
+        count_stones(1, blinks - 1, seen)
+    } else if digits.len().is_multiple_of(2) {
+        let (left, right) = digits.split_at(digits.len() / 2);
+        count_stones(left.parse().unwrap(), blinks - 1, seen)
+            + count_stones(right.parse().unwrap(), blinks - 1, seen)
+    } else {
+        count_stones(stone * 2024, blinks - 1, seen)
+    };
+    seen.insert((stone, blinks), count);
+    count
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, mut stones) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    (0..25).for_each(|_| {
-        stones = stones.iter().copied().flat_map(rules).collect();
-    });
-    stones.len().to_string()
+    common::solve_part1::<Day11>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, stones) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let mut stones: HashMap<u64, u64> = stones.into_iter().map(|n| (n, 1)).collect();
-    (0..75).for_each(|_| {
-        blink(&mut stones);
-    });
-    stones.into_values().sum::<u64>().to_string()
+    common::solve_part2::<Day11>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day11>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day11>(input)
 }
 
-fn rules(n: u64) -> impl Iterator<Item = u64> {
+/// The stones a stone becomes; stones never outgrow a `u128`, as an odd number of digits grows by
+/// at most seven before it splits.
+fn rules(n: u128) -> impl Iterator<Item = u128> {
     let new_stones = {
         if n == 0 {
             [Some(1), None]
         } else {
             let digits = n.ilog10() + 1;
-            if digits % 2 == 0 {
-                let pow10 = 10_u64.pow(digits / 2);
+            if digits.is_multiple_of(2) {
+                let pow10 = 10_u128.pow(digits / 2);
                 [Some(n / pow10), Some(n % pow10)]
             } else {
                 [Some(n * 2024), None]
@@ -41,34 +144,13 @@ fn rules(n: u64) -> impl Iterator<Item = u64> {
     new_stones.into_iter().flatten()
 }
 
-fn blink(stones: &mut HashMap<u64, u64>) {
-    let mut new_stones = HashMap::new();
-    stones.drain().for_each(|(k, v)| {
-        rules(k).for_each(|new_stone| {
-            new_stones
-                .entry(new_stone)
-                .and_modify(|new_v| *new_v += v)
-                .or_insert(v);
-        })
-    });
-
-    std::mem::swap(stones, &mut new_stones);
-}
-
 fn parse_input(input: &str) -> IResult<&str, Vec<u64>> {
-    let (input, stones) = separated_list1(space1, nomu64)(input)?;
-    Ok((input, stones))
+    spaced(cut(unsigned))(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
-    #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "55312");
-    }
+    common::day_tests!(Day11);
 }
@@ -1,27 +1,165 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::{i64 as nomi64, newline},
-    multi::separated_list1,
-    sequence::pair,
-    IResult,
+use std::io::BufRead;
+
+use common::{
+    Generate, IResult, OrOverflow, Overflow, ParseError, Reference, Rng, Separator, Solution,
+    SolveError, Stream,
 };
 
+use nom::bytes::complete::tag;
+use parsing::{blocks, signed};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<Machine>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        common::finish(input, parse_input(input))
+    }
+
+    fn part1(machines: &Self::Parsed) -> i64 {
+        Self::checked_part1(machines).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn part2(machines: &Self::Parsed) -> i64 {
+        Self::checked_part2(machines).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn checked_part1(machines: &Self::Parsed) -> Result<i64, Overflow> {
+        total(common::par_map(machines, tokens))
+    }
+
+    fn checked_part2(machines: &Self::Parsed) -> Result<i64, Overflow> {
+        total(common::par_map(machines, tokens2))
+    }
+}
+
+impl Stream for Day13 {
+    const SEPARATOR: Separator = Separator::BlankLine;
+
+    type Record = Machine;
+    /// The fewest tokens to win the prizes so far.
+    type Tally1 = i64;
+    type Tally2 = i64;
+
+    fn parse_record(block: &str) -> Result<Machine, ParseError> {
+        common::finish(block, Machine::parse(block))
+    }
+
+    fn tally1(total: &mut i64, machine: &Machine) -> Result<(), Overflow> {
+        *total = add_tokens(*total, tokens(machine)?)?;
+        Ok(())
+    }
+
+    fn tally2(total: &mut i64, machine: &Machine) -> Result<(), Overflow> {
+        *total = add_tokens(*total, tokens2(machine)?)?;
+        Ok(())
+    }
+
+    fn answer1(total: i64) -> Result<i64, Overflow> {
+        Ok(total)
+    }
+
+    fn answer2(total: i64) -> Result<i64, Overflow> {
+        Ok(total)
+    }
+}
+
+/// `size` is the number of claw machines. The buttons never move the claw in the same direction,
+/// and about half of the prizes can be won.
+impl Generate for Day13 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut machines = Vec::new();
+        for _ in 0..size {
+            let a = (rng.range(10..=99), rng.range(10..=99));
+            let b = loop {
+                let b = (rng.range(10..=99), rng.range(10..=99));
+                if a.0 * b.1 != a.1 * b.0 {
+                    break b;
+                }
+            };
+            let prize = if rng.chance(0.5) {
+                let (presses_a, presses_b) = (rng.range(0..=100), rng.range(0..=100));
+                (
+                    presses_a * a.0 + presses_b * b.0,
+                    presses_a * a.1 + presses_b * b.1,
+                )
+            } else {
+                (rng.range(1_000..=20_000), rng.range(1_000..=20_000))
+            };
+            machines.push(format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            ));
+        }
+        machines.join("\n")
+    }
+}
+
+impl Reference for Day13 {
+    /// Tries every number of presses up to 100 of each button.
+    fn reference_part1(machines: &Self::Parsed) -> i64 {
+        machines
+            .iter()
+            .filter_map(|m| {
+                (0..=100)
+                    .flat_map(|a| (0..=100).map(move |b| (a, b)))
+                    .filter(|&(a, b)| (a * m.a.0 + b * m.b.0, a * m.a.1 + b * m.b.1) == m.prize)
+                    .map(|(a, b)| 3 * a + b)
+                    .min()
+            })
+            .sum()
+    }
+
+    /// Solves each machine's two equations with Cramer's rule in 128 bits, which only works for
+    /// buttons that move the claw in different directions, like the generated ones.
+    fn reference_part2(machines: &Self::Parsed) -> i64 {
+        machines
+            .iter()
+            .filter_map(|m| {
+                let [ax, ay, bx, by] = [m.a.0, m.a.1, m.b.0, m.b.1].map(i128::from);
+                let (px, py) = (
+                    i128::from(m.prize.0) + 10_000_000_000_000,
+                    i128::from(m.prize.1) + 10_000_000_000_000,
+                );
+                let determinant = ax * by - ay * bx;
+                let a = (px * by - py * bx) / determinant;
+                let b = (ax * py - ay * px) / determinant;
+                let wins = a >= 0 && b >= 0 && (a * ax + b * bx, a * ay + b * by) == (px, py);
+                wins.then(|| i64::try_from(3 * a + b).unwrap())
+            })
+            .sum()
+    }
+}
+
 pub fn process_part1(input: &str) -> String {
-    let (input, machines) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let total_tokens = machines.into_iter().map(tokens).sum::<i64>();
-    total_tokens.to_string()
+    common::solve_part1::<Day13>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let (input, machines) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let total_tokens = machines.into_iter().map(tokens2).sum::<i64>();
-    total_tokens.to_string()
+    common::solve_part2::<Day13>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day13>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day13>(input)
+}
+
+pub fn stream_part1(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part1::<Day13>(input)
+}
+
+pub fn stream_part2(input: &mut dyn BufRead) -> Result<String, SolveError> {
+    common::try_stream_part2::<Day13>(input)
 }
 
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
     a: (i64, i64),
     b: (i64, i64),
     prize: (i64, i64),
@@ -30,17 +168,17 @@ struct Machine {
 impl Machine {
     fn parse(input: &str) -> IResult<&str, Self> {
         let (input, _) = tag("Button A: X+")(input)?;
-        let (input, ax) = nomi64(input)?;
+        let (input, ax) = signed(input)?;
         let (input, _) = tag(", Y+")(input)?;
-        let (input, ay) = nomi64(input)?;
+        let (input, ay) = signed(input)?;
         let (input, _) = tag("\nButton B: X+")(input)?;
-        let (input, bx) = nomi64(input)?;
+        let (input, bx) = signed(input)?;
         let (input, _) = tag(", Y+")(input)?;
-        let (input, by) = nomi64(input)?;
+        let (input, by) = signed(input)?;
         let (input, _) = tag("\nPrize: X=")(input)?;
-        let (input, px) = nomi64(input)?;
+        let (input, px) = signed(input)?;
         let (input, _) = tag(", Y=")(input)?;
-        let (input, py) = nomi64(input)?;
+        let (input, py) = signed(input)?;
         let machine = Machine {
             a: (ax, ay),
             b: (bx, by),
@@ -50,56 +188,209 @@ impl Machine {
     }
 }
 
-fn tokens(machine: Machine) -> i64 {
-    let (u, v) = machine.a;
-    let (w, z) = machine.b;
-    let (c, d) = machine.prize;
-    let zcwd = (z * c) - (w * d);
-    let uzvw = (u * z) - (v * w);
-    if zcwd % uzvw == 0 {
-        let a = zcwd / uzvw;
-        let cua = c - u * a;
-        if cua % w == 0 {
-            let b = cua / w;
-            return a * 3 + b;
-        }
+/// The cheapest presses of A and B, none negative and at most `limit` of each if there is one,
+/// that move the claw exactly onto a prize `offset` further away on both axes, if there are any.
+/// The products of two coordinates always fit in 128 bits; only their differences can overflow,
+/// with coordinates close to the 64-bit limits.
+fn presses(
+    machine: &Machine,
+    offset: i64,
+    limit: Option<i128>,
+) -> Result<Option<(i128, i128)>, Overflow> {
+    let [u, v, w, z] = [machine.a.0, machine.a.1, machine.b.0, machine.b.1].map(i128::from);
+    let c = i128::from(machine.prize.0) + i128::from(offset);
+    let d = i128::from(machine.prize.1) + i128::from(offset);
+    if u * z == v * w {
+        // the buttons move the claw along the same line, so the prize has to be on it too
+        return Ok(if (u, w) != (0, 0) {
+            (u * d == v * c && w * d == z * c)
+                .then(|| cheapest_on_line(u, w, c, limit))
+                .flatten()
+        } else if (v, z) != (0, 0) {
+            (c == 0).then(|| cheapest_on_line(v, z, d, limit)).flatten()
+        } else {
+            (c == 0 && d == 0).then_some((0, 0))
+        });
+    }
+    let presses = common::solve_2x2([[u, w], [v, z]], [c, d])
+        .map_err(|_| Overflow {
+            computing: "the presses for a prize".to_string(),
+        })?
+        .map(|[a, b]| (a, b))
+        .filter(|&(a, b)| {
+            let allowed =
+                |presses: i128| presses >= 0 && limit.is_none_or(|limit| presses <= limit);
+            allowed(a) && allowed(b)
+        });
+    Ok(presses)
+}
+
+/// The cheapest whole `a` and `b`, none negative and at most `limit` if there is one, with
+/// `p * a + q * b == r`, where `p` and `q` are not both 0. The numbers on the way stay within a
+/// few times the products of two coordinates.
+fn cheapest_on_line(p: i128, q: i128, r: i128, limit: Option<i128>) -> Option<(i128, i128)> {
+    if q == 0 {
+        // B does not move the claw along the line, so it is never worth pressing
+        let a = (r % p == 0).then_some(r / p)?;
+        let allowed = a >= 0 && limit.is_none_or(|limit| a <= limit);
+        return allowed.then_some((a, 0));
+    }
+    let g = common::gcd(p, q);
+    if r % g != 0 {
+        return None;
     }
-    0
-}
-
-fn tokens2(machine: Machine) -> i64 {
-    let (u, v) = machine.a;
-    let (w, z) = machine.b;
-    let (c, d) = machine.prize;
-    let c = c + 10_000_000_000_000;
-    let d = d + 10_000_000_000_000;
-    let zcwd = (z * c) - (w * d);
-    let uzvw = (u * z) - (v * w);
-    if zcwd % uzvw == 0 {
-        let a = zcwd / uzvw;
-        let cua = c - u * a;
-        if cua % w == 0 {
-            let b = cua / w;
-            return a * 3 + b;
+    // the solutions are a = a0 + k * m and b = b0 + k * s for every whole k, where a0 is the
+    // smallest a that is not negative
+    let m = (q / g).abs();
+    let inverse = common::mod_inverse(p / g, m).expect("p / g and q / g have no common divisor");
+    let a0 = ((r / g).rem_euclid(m) * inverse).rem_euclid(m);
+    let b0 = (r - p * a0) / q;
+    let s = -(p / g) * q.signum();
+    let floor = |n: i128, d: i128| n.div_euclid(d);
+    let ceil = |n: i128, d: i128| -(-n).div_euclid(d);
+    // narrow k down to where neither a nor b is negative or above the limit
+    let mut low = 0;
+    let mut high = limit.map(|limit| floor(limit - a0, m));
+    let mut below = |bound: i128| high = Some(high.map_or(bound, |high| high.min(bound)));
+    match s.signum() {
+        1 => {
+            low = low.max(ceil(-b0, s));
+            if let Some(limit) = limit {
+                below(floor(limit - b0, s));
+            }
         }
+        -1 => {
+            below(floor(b0, -s));
+            if let Some(limit) = limit {
+                low = low.max(ceil(b0 - limit, -s));
+            }
+        }
+        _ if b0 < 0 || limit.is_some_and(|limit| b0 > limit) => return None,
+        _ => {}
+    }
+    if high.is_some_and(|high| high < low) {
+        return None;
     }
-    0
+    // every step of k changes the cost by 3 * m + s, so the cheapest is at one end
+    let k = match high {
+        Some(high) if 3 * m + s < 0 => high,
+        _ => low,
+    };
+    Some((a0 + k * m, b0 + k * s))
+}
+
+/// The tokens it takes to press A `a` times and B `b` times.
+fn cost(a: i128, b: i128) -> Result<i64, Overflow> {
+    let tokens = a.checked_mul(3).and_then(|a| a.checked_add(b));
+    tokens
+        .and_then(|tokens| i64::try_from(tokens).ok())
+        .or_overflow("the tokens for a prize")
+}
+
+fn tokens(machine: &Machine) -> Result<i64, Overflow> {
+    // no button can be pressed more than 100 times
+    match presses(machine, 0, Some(100))? {
+        Some((a, b)) => cost(a, b),
+        None => Ok(0),
+    }
+}
+
+fn tokens2(machine: &Machine) -> Result<i64, Overflow> {
+    match presses(machine, 10_000_000_000_000, None)? {
+        Some((a, b)) => cost(a, b),
+        None => Ok(0),
+    }
+}
+
+/// The fewest tokens that win every prize that can be won.
+fn total(tokens: Vec<Result<i64, Overflow>>) -> Result<i64, Overflow> {
+    tokens
+        .into_iter()
+        .try_fold(0_i64, |total, tokens| add_tokens(total, tokens?))
+}
+
+fn add_tokens(total: i64, tokens: i64) -> Result<i64, Overflow> {
+    total
+        .checked_add(tokens)
+        .or_overflow("the total number of tokens")
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Machine>> {
-    let (input, lines) = separated_list1(pair(newline, newline), Machine::parse)(input)?;
-    Ok((input, lines))
+    blocks(Machine::parse)(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+
+    common::day_tests!(Day13, stream, parallel);
+
+    #[test]
+    fn parallel_buttons() {
+        let machine = |a: (i64, i64), b: (i64, i64), prize: (i64, i64)| {
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            )
+        };
+        let cases = [
+            // B twice is cheaper than A twice
+            (machine((1, 1), (2, 2), (4, 4)), 2),
+            // 125 presses of B are too many, so A has to help
+            (machine((1, 1), (2, 2), (250, 250)), 250),
+            // A moves four times as far for three tokens
+            (machine((4, 4), (1, 1), (8, 8)), 6),
+            (machine((1, 1), (2, 2), (4, 5)), 0),
+            (machine((2, 0), (3, 0), (7, 0)), 7),
+            (machine((0, 2), (0, 3), (0, 7)), 7),
+            (machine((0, 2), (0, 3), (1, 7)), 0),
+            (machine((2, 4), (1, 2), (8, 16)), 8),
+            (machine((0, 0), (0, 0), (0, 0)), 0),
+        ];
+        for (input, tokens) in cases {
+            let machines = Day13::parse(&input).unwrap();
+            assert_eq!(Day13::part1(&machines), tokens, "{input}");
+            assert_eq!(Day13::reference_part1(&machines), tokens, "{input}");
+        }
+        let input = machine((1, 1), (2, 2), (0, 0));
+        assert_eq!(process_part2(&input), "5000000000000");
+        let input = machine((2, 4), (0, 2), (8, 18));
+        assert_eq!(process_part1(&input), "13");
+    }
 
     #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "480");
+    fn extreme_coordinates() {
+        let machine = |a: (i64, i64), b: (i64, i64), prize: (i64, i64)| {
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}",
+                a.0, a.1, b.0, b.1, prize.0, prize.1
+            )
+        };
+        let max = i64::MAX;
+        // 100 presses of A and none of B
+        let input = machine((max / 100, 1), (1, max / 100), (max / 100 * 100, 100));
+        assert_eq!(process_part1(&input), "300");
+        // i64::MAX / 3 presses of A cost nearly i64::MAX tokens
+        let costly = machine(
+            (1, 0),
+            (0, 1),
+            (max / 3 - 10_000_000_000_000, -10_000_000_000_000),
+        );
+        assert_eq!(process_part2(&costly), (max / 3 * 3).to_string());
+        let input = machine((1, 0), (0, 1), (max - 10_000_000_000_000, 0));
+        assert_eq!(
+            try_process_part2(&input).unwrap_err().to_string(),
+            "arithmetic overflow computing the tokens for a prize"
+        );
+        let input = machine((1, 0), (i64::MIN, max), (max, max));
+        assert_eq!(
+            try_process_part2(&input).unwrap_err().to_string(),
+            "arithmetic overflow computing the presses for a prize"
+        );
+        let input = format!("{costly}\n\n{costly}");
+        assert_eq!(
+            try_process_part2(&input).unwrap_err().to_string(),
+            "arithmetic overflow computing the total number of tokens"
+        );
     }
 }
@@ -1,56 +1,208 @@
+use common::{
+    Generate, IResult, OrOverflow, Overflow, ParseError, Reference, Rng, Solution, SolveError,
+};
+
+use grid::Grid;
 use nom::{
     bytes::complete::tag,
-    character::complete::{char as nomchar, digit1, newline, u32 as nomu32},
-    combinator::opt,
-    multi::separated_list1,
+    character::complete::{char as nomchar, newline},
     sequence::terminated,
-    IResult,
 };
-use std::cmp::Ordering;
+use parsing::{lines, signed, unsigned};
+use std::{cmp::Ordering, iter};
+use visualize::{Cell, Frame, Rgb, Simulation, Visualize};
 
-pub fn process_part1(input: &str) -> String {
-    let (input, ((width, height), mut robots)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    let mut quadrants: [u32; 5] = [0; 5];
-    robots.iter_mut().for_each(|r| {
-        r.walk(width, height, 100);
-        let q = r.quadrant(width, height);
-        quadrants[q] += 1;
-    });
-    let safety_factor: u32 = quadrants.into_iter().skip(1).product();
-    safety_factor.to_string()
-}
+pub struct Day14;
 
-pub fn process_part2(input: &str) -> String {
-    let (input, ((width, height), mut robots)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
+impl Solution for Day14 {
+    type Parsed = ((u32, u32), Vec<Robot>);
+    type Answer1 = u64;
+    type Answer2 = usize;
 
-    let mut christmas_egg = 0;
-
-    for n in 100..20000 {
-        if n % 101 == 13 && n % 103 == 79 {
-            christmas_egg = n;
-            break;
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let ((width, height), robots) = common::finish(input, parse_input(input))?;
+        if width == 0 || height == 0 {
+            return Err(ParseError::at(
+                input,
+                input,
+                "a room at least one tile wide and high",
+            ));
+        }
+        for (robot, line) in robots.iter().zip(input.lines().skip(1)) {
+            if robot.pos.0 >= width || robot.pos.1 >= height {
+                return Err(ParseError::at(input, line, "a robot inside the room"));
+            }
         }
+        Ok(((width, height), robots))
+    }
+
+    fn part1(parsed: &Self::Parsed) -> u64 {
+        Self::checked_part1(parsed).unwrap_or_else(|e| panic!("{e}"))
     }
 
-    let start = christmas_egg;
-    robots.iter_mut().for_each(|r| {
-        r.walk(width, height, start - 1);
-    });
-    (start..(start + 1)).for_each(|i| {
+    /// The robots form the Easter egg when they are bunched up the most. Along each axis they
+    /// come back to where they started every so many seconds, so the seconds at which they are
+    /// bunched up the most along both axes at once follow from the Chinese remainder theorem.
+    fn part2(((width, height), robots): &Self::Parsed) -> usize {
+        let xs = spreads(robots.iter().map(|r| (r.pos.0, r.vel.0)), *width);
+        let ys = spreads(robots.iter().map(|r| (r.pos.1, r.vel.1)), *height);
+        let (x_period, y_period) = (xs.len() as i128, ys.len() as i128);
+        let seconds = tightest(&xs).flat_map(|x| tightest(&ys).map(move |y| (x, y)));
+        let together = seconds.filter_map(|(x, y)| {
+            common::crt(&[(x as i128, x_period), (y as i128, y_period)])
+                .expect("the product of two periods fits in 128 bits")
+                .map(|(second, _)| second as usize)
+        });
+        together.min().unwrap_or_else(|| {
+            // periods with a common divisor can keep the tightest seconds apart, leaving the
+            // least spread out second of all to be found one by one
+            (0..xs.len() * ys.len())
+                .min_by_key(|&second| xs[second % xs.len()] + ys[second % ys.len()])
+                .expect("periods are at least one second")
+        })
+    }
+
+    fn checked_part1(((width, height), robots): &Self::Parsed) -> Result<u64, Overflow> {
+        let (width, height) = (*width, *height);
+        let mut robots = robots.clone();
+        let mut quadrants: [u64; 5] = [0; 5];
         robots.iter_mut().for_each(|r| {
-            r.walk(width, height, 1);
+            r.walk(width, height, 100);
+            let q = r.quadrant(width, height);
+            quadrants[q] += 1;
+        });
+        quadrants
+            .into_iter()
+            .skip(1)
+            .try_fold(1_u64, |factor, robots| {
+                factor.checked_mul(robots).or_overflow("the safety factor")
+            })
+    }
+}
+
+/// `size` is the number of robots, in a room with odd sides of up to 101 by 103 tiles.
+impl Generate for Day14 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let width = 2 * rng.range(1..=50) + 1;
+        let height = 2 * rng.range(1..=51) + 1;
+        let mut input = format!("{width}x{height}\n");
+        for _ in 0..size {
+            input.push_str(&format!(
+                "p={},{} v={},{}\n",
+                rng.range(0..=width - 1),
+                rng.range(0..=height - 1),
+                rng.range(1 - width..=width - 1),
+                rng.range(1 - height..=height - 1)
+            ));
+        }
+        input
+    }
+}
+
+impl Reference for Day14 {
+    /// Moves every robot one second at a time and counts each quadrant separately.
+    fn reference_part1(((width, height), robots): &Self::Parsed) -> u64 {
+        let (width, height) = (i64::from(*width), i64::from(*height));
+        let positions = robots.iter().map(|r| {
+            let (mut x, mut y) = (i64::from(r.pos.0), i64::from(r.pos.1));
+            for _ in 0..100 {
+                x = (x + i64::from(r.vel.0)).rem_euclid(width);
+                y = (y + i64::from(r.vel.1)).rem_euclid(height);
+            }
+            (x, y)
         });
-        println!("{i}:");
-        print_robots(&robots, width, height);
-        println!("________________________________________")
-    });
-    christmas_egg.to_string()
+        let (mid_x, mid_y) = (width / 2, height / 2);
+        let mut quadrants = [0; 4];
+        for (x, y) in positions {
+            if x != mid_x && y != mid_y {
+                quadrants[usize::from(x > mid_x) + 2 * usize::from(y > mid_y)] += 1;
+            }
+        }
+        quadrants.into_iter().product()
+    }
+
+    /// Moves every robot one second at a time through all the seconds before the whole room is
+    /// back where it started, measuring how bunched up they are at each.
+    fn reference_part2(((width, height), robots): &Self::Parsed) -> usize {
+        let mut robots = robots.clone();
+        let mut best = (u128::MAX, 0);
+        for second in 0..*width as usize * *height as usize {
+            let xs = robots.iter().map(|r| r.pos.0).collect::<Vec<_>>();
+            let ys = robots.iter().map(|r| r.pos.1).collect::<Vec<_>>();
+            best = best.min((spread(&xs) + spread(&ys), second));
+            for robot in &mut robots {
+                robot.walk(*width, *height, 1);
+            }
+        }
+        best.1
+    }
+}
+
+/// Both parts watch the robots walk; skip to the answer of part 2 to see the tree.
+impl Visualize for Day14 {
+    fn simulation(((width, height), robots): &Self::Parsed, _part: u8) -> Box<dyn Simulation> {
+        Box::new(Room {
+            width: *width,
+            height: *height,
+            robots: robots.clone(),
+            seconds: 0,
+        })
+    }
+}
+
+/// The robots walking around the room, a second at a time. They never stop.
+pub struct Room {
+    width: u32,
+    height: u32,
+    robots: Vec<Robot>,
+    seconds: usize,
+}
+
+impl Simulation for Room {
+    fn frame(&self) -> Frame {
+        let mut frame = Grid::filled(
+            self.width as usize,
+            self.height as usize,
+            Cell::new('.', Rgb::BACKGROUND),
+        );
+        for robot in &self.robots {
+            let (x, y) = robot.pos;
+            frame[(x as usize, y as usize)] = Cell::new('#', Rgb::CROWD);
+        }
+        frame
+    }
+
+    fn step(&mut self) -> bool {
+        for robot in &mut self.robots {
+            robot.walk(self.width, self.height, 1);
+        }
+        self.seconds += 1;
+        true
+    }
+
+    fn caption(&self) -> String {
+        format!("after {} seconds", self.seconds)
+    }
+}
+
+pub fn process_part1(input: &str) -> String {
+    common::solve_part1::<Day14>(input)
+}
+
+pub fn process_part2(input: &str) -> String {
+    common::solve_part2::<Day14>(input)
 }
 
-#[derive(Debug)]
-struct Robot {
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day14>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day14>(input)
+}
+
+#[derive(Debug, Clone)]
+pub struct Robot {
     pos: (u32, u32),
     vel: (i32, i32),
 }
@@ -58,29 +210,25 @@ struct Robot {
 impl Robot {
     fn parse(input: &str) -> IResult<&str, Self> {
         let (input, _) = tag("p=")(input)?;
-        let (input, px) = nomu32(input)?;
+        let (input, px) = unsigned(input)?;
         let (input, _) = nomchar(',')(input)?;
-        let (input, py) = nomu32(input)?;
+        let (input, py) = unsigned(input)?;
         let pos = (px, py);
         let (input, _) = tag(" v=")(input)?;
-        let (input, vx) = parse_i32(input)?;
+        let (input, vx) = signed(input)?;
         let (input, _) = nomchar(',')(input)?;
-        let (input, vy) = parse_i32(input)?;
+        let (input, vy) = signed(input)?;
         let vel = (vx, vy);
         Ok((input, Self { pos, vel }))
     }
 
     fn walk(&mut self, width: u32, height: u32, steps: usize) {
         let (vx, vy) = self.vel;
-        let w = width as i32;
-        let h = height as i32;
-        let s = steps as i32;
-        let vx = (vx + w) % w;
-        let vy = (vy + h) % h;
-        let (mut px, mut py) = self.pos;
-        px = ((px as i32 + vx * s) % w) as u32;
-        py = ((py as i32 + vy * s) % h) as u32;
-        self.pos = (px, py);
+        let (px, py) = self.pos;
+        self.pos = (
+            advance(px, vx, width, steps),
+            advance(py, vy, height, steps),
+        );
     }
 
     fn quadrant(&self, width: u32, height: u32) -> usize {
@@ -98,52 +246,138 @@ impl Robot {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, ((u32, u32), Vec<Robot>)> {
-    let (input, width) = terminated(nomu32, nomchar('x'))(input)?;
-    let (input, height) = terminated(nomu32, newline)(input)?;
+/// Where a robot at `p` on an axis of length `side` is after `steps` seconds at `v` a second.
+/// Every number is reduced modulo the side before it is multiplied, so the product of two of
+/// them, plus a position, always fits in a `u64`.
+fn advance(p: u32, v: i32, side: u32, steps: usize) -> u32 {
+    let side = u64::from(side);
+    let v = i64::from(v).rem_euclid(side as i64) as u64;
+    let steps = steps as u64 % side;
+    ((u64::from(p) + v * steps) % side) as u32
+}
 
-    let (input, robots) = separated_list1(newline, Robot::parse)(input)?;
-    Ok((input, ((width, height), robots)))
+/// How spread out robots with these positions and velocities are along an axis, every second
+/// until they are all back where they started.
+fn spreads(robots: impl Iterator<Item = (u32, i32)>, side: u32) -> Vec<u128> {
+    let (positions, velocities): (Vec<u32>, Vec<i32>) = robots.unzip();
+    let second = |positions: &Vec<u32>| {
+        positions
+            .iter()
+            .zip(&velocities)
+            .map(|(&p, &v)| advance(p, v, side, 1))
+            .collect::<Vec<_>>()
+    };
+    // a second can be undone, so the positions come round to the very first ones
+    let period = common::brent(positions.clone(), second).length;
+    iter::successors(Some(positions), |positions| Some(second(positions)))
+        .take(period)
+        .map(|positions| spread(&positions))
+        .collect()
 }
 
-fn parse_i32(input: &str) -> IResult<&str, i32> {
-    let (input, sign) = opt(nomchar('-'))(input)?;
-    let (input, n) = digit1(input)?;
-    let mut i = n.parse::<i32>().expect("Invalid digits");
-    if sign.is_some() {
-        i = -i;
-    }
+/// The seconds at which the robots are the least spread out along an axis.
+fn tightest(spreads: &[u128]) -> impl Iterator<Item = usize> + '_ {
+    let least = spreads.iter().min().copied();
+    (0..spreads.len()).filter(move |&second| Some(spreads[second]) == least)
+}
 
-    Ok((input, i))
+/// The variance of `positions` times their number squared, which keeps it a whole number.
+fn spread(positions: &[u32]) -> u128 {
+    let n = positions.len() as u128;
+    let sum = positions.iter().map(|&p| u128::from(p)).sum::<u128>();
+    let squares = positions
+        .iter()
+        .map(|&p| u128::from(p).pow(2))
+        .sum::<u128>();
+    n * squares - sum * sum
 }
 
-fn print_robots(robots: &[Robot], width: u32, height: u32) {
-    let len = (width * height) as usize;
-    let mut string = String::with_capacity(len);
-    string.extend(std::iter::repeat('.').take(len));
-    robots.iter().for_each(|r| {
-        let (x, y) = r.pos;
-        let strpos = (y * width + x) as usize;
-        assert!(strpos < string.len());
-        unsafe {
-            let bytes = string.as_bytes_mut();
-            bytes[strpos] = b'X';
-        }
-    });
-    for chunk in string.as_bytes().chunks(width as usize) {
-        println!("{}", std::str::from_utf8(chunk).unwrap());
-    }
+fn parse_input(input: &str) -> IResult<&str, ((u32, u32), Vec<Robot>)> {
+    let (input, width) = terminated(unsigned, nomchar('x'))(input)?;
+    let (input, height) = terminated(unsigned, newline)(input)?;
+
+    let (input, robots) = lines(Robot::parse)(input)?;
+    Ok((input, ((width, height), robots)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+
+    common::day_tests!(Day14);
+
+    #[test]
+    fn invalid_robots() {
+        let Err(SolveError::Parse(error)) = try_process_part1("11x7\np=0,4 v=3,-99999999999")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(error.expected, "a number");
+        let Err(SolveError::Parse(error)) = try_process_part1("11x7\np=0,4 v=3,-3\np=11,4 v=3,-3")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.expected, "a robot inside the room");
+        let Err(SolveError::Parse(error)) = try_process_part1("0x7\np=0,4 v=3,-3") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.expected, "a room at least one tile wide and high");
+    }
+
+    #[test]
+    fn huge_rooms() {
+        let max = u32::MAX;
+        let input = format!("{max}x3\np=0,0 v={},1\np={},2 v=-1,0", i32::MAX, max - 1);
+        let (_, robots) = Day14::parse(&input).unwrap();
+        let mut robot = robots[0].clone();
+        robot.walk(max, 3, usize::MAX);
+        let steps = (usize::MAX as u64 % u64::from(max)) as u128;
+        let x = (steps * i32::MAX as u128 % u128::from(max)) as u32;
+        assert_eq!(robot.pos, (x, 0));
+        let mut robot = robots[1].clone();
+        robot.walk(max, 3, 100);
+        assert_eq!(robot.pos, (max - 101, 2));
+    }
+
+    #[test]
+    fn overflowing_safety_factor() {
+        let robots = ["p=0,0 v=0,0", "p=2,0 v=0,0", "p=0,2 v=0,0", "p=2,2 v=0,0"];
+        let input = format!(
+            "3x3\n{}",
+            robots.map(|r| format!("{r}\n").repeat(70_000)).concat()
+        );
+        assert_eq!(
+            try_process_part1(&input).unwrap_err().to_string(),
+            "arithmetic overflow computing the safety factor"
+        );
+        let input = format!(
+            "3x3\n{}",
+            robots.map(|r| format!("{r}\n").repeat(300)).concat()
+        );
+        assert_eq!(process_part1(&input), (300_u64.pow(4)).to_string());
+    }
+
+    #[test]
+    fn shared_periods() {
+        // both axes take 3 seconds to come round; the robots bunch up along x at second 1 and
+        // along y at second 2, but never along both
+        let input = "3x3\np=0,0 v=1,1\np=2,1 v=-1,-1\np=1,1 v=1,-1";
+        let parsed = Day14::parse(input).unwrap();
+        assert_eq!(Day14::part2(&parsed), 1);
+        assert_eq!(Day14::reference_part2(&parsed), 1);
+        // the robots meet along x after 50 seconds and along y after 51, every 101 and 103
+        let input = "101x103\np=0,0 v=1,1\np=100,102 v=-1,-1";
+        assert_eq!(process_part2(input), "5201");
+    }
 
     #[test]
-    fn part1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "12");
+    fn watches_the_robots() {
+        let mut room = visualize::simulate::<Day14>("5x3\np=2,1 v=2,-3\np=0,0 v=0,0", 1).unwrap();
+        assert_eq!(visualize::text(&room.frame()), "#....\n..#..\n.....\n");
+        assert_eq!(visualize::skip(room.as_mut(), 2), 2);
+        assert_eq!(visualize::text(&room.frame()), "#....\n.#...\n.....\n");
+        assert_eq!(room.caption(), "after 2 seconds");
     }
 }
@@ -1,28 +1,213 @@
-use std::fmt::Debug;
+use std::{collections::HashSet, fmt::Debug};
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{newline, one_of},
-    error::make_error,
-    multi::{many1, separated_list1},
-    sequence::terminated,
-    IResult,
-};
+use common::{Generate, ParseError, Reference, Rng, Solution, SolveError};
+use grid::{Direction, Grid, Point};
+use visualize::{Cell, Frame, Rgb, Simulation, Visualize};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Parsed = (Warehouse, Vec<Direction>);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let (warehouse, moves) = parsing::split_sections(input, "the moves")?;
+        Ok((parse_warehouse(warehouse)?, parse_moves(input, moves)?))
+    }
+
+    fn part1((warehouse, moves): &Self::Parsed) -> usize {
+        let mut warehouse = warehouse.clone();
+        warehouse.move_robot(moves);
+        warehouse.sum()
+    }
+
+    fn part2((warehouse, moves): &Self::Parsed) -> usize {
+        let mut warehouse = Warehouse2::from(warehouse);
+        warehouse.move_robot(moves);
+        warehouse.sum()
+    }
+}
+
+/// `size` is the side of the warehouse floor inside its walls; there are twenty moves per unit of
+/// size.
+impl Generate for Day15 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let side = size + 2;
+        let robot = (
+            rng.range(1..=size as i64) as usize,
+            rng.range(1..=size as i64) as usize,
+        );
+        let mut input = String::new();
+        for y in 0..side {
+            for x in 0..side {
+                input.push(match (x, y) {
+                    pos if pos == robot => '@',
+                    _ if x == 0 || y == 0 || x == side - 1 || y == side - 1 => '#',
+                    _ if rng.chance(0.05) => '#',
+                    _ if rng.chance(0.3) => 'O',
+                    _ => '.',
+                });
+            }
+            input.push('\n');
+        }
+        for i in 0..20 * size {
+            if i % 70 == 0 {
+                input.push('\n');
+            }
+            input.push(rng.choose(&Direction::ALL).arrow());
+        }
+        input.push('\n');
+        input
+    }
+}
+
+impl Reference for Day15 {
+    fn reference_part1((warehouse, moves): &Self::Parsed) -> usize {
+        gps_after_moves(warehouse, moves, 1)
+    }
+
+    fn reference_part2((warehouse, moves): &Self::Parsed) -> usize {
+        gps_after_moves(warehouse, moves, 2)
+    }
+}
+
+/// Moves the robot around the warehouse stretched `scale` times horizontally, keeping walls and
+/// the left edge of every box in sets, and sums the GPS coordinates of the boxes.
+fn gps_after_moves(warehouse: &Warehouse, moves: &[Direction], scale: usize) -> usize {
+    let mut walls = HashSet::new();
+    let mut boxes = HashSet::new();
+    for ((x, y), space) in warehouse.contents.iter() {
+        match space {
+            Space::Wall => walls.extend((0..scale).map(|i| (x * scale + i, y))),
+            Space::Box => {
+                boxes.insert((x * scale, y));
+            }
+            Space::Empty => {}
+        }
+    }
+    let mut robot = (warehouse.robot.x * scale, warehouse.robot.y);
+    let (width, height) = (
+        warehouse.contents.width() * scale,
+        warehouse.contents.height(),
+    );
+    let step = |(x, y): (usize, usize), direction: Direction| {
+        let v = direction.vector();
+        let x = x.checked_add_signed(v.dx).filter(|&x| x < width)?;
+        let y = y.checked_add_signed(v.dy).filter(|&y| y < height)?;
+        Some((x, y))
+    };
+    for &direction in moves {
+        let Some(next) = step(robot, direction) else {
+            continue;
+        };
+        let mut pushed = HashSet::new();
+        let mut ahead = vec![Some(next)];
+        let mut blocked = false;
+        while let Some(pos) = ahead.pop() {
+            // the edge of the warehouse stops boxes like a wall
+            let Some(pos) = pos.filter(|pos| !walls.contains(pos)) else {
+                blocked = true;
+                break;
+            };
+            let left_edge = (0..scale)
+                .map(|i| (pos.0.wrapping_sub(i), pos.1))
+                .find(|left_edge| boxes.contains(left_edge));
+            if let Some(left_edge) = left_edge {
+                if pushed.insert(left_edge) {
+                    ahead.extend(
+                        (0..scale).map(|i| step((left_edge.0 + i, left_edge.1), direction)),
+                    );
+                }
+            }
+        }
+        if !blocked {
+            for left_edge in &pushed {
+                boxes.remove(left_edge);
+            }
+            // none of the boxes is at the edge, or they would have been blocked
+            boxes.extend(
+                pushed
+                    .into_iter()
+                    .filter_map(|left_edge| step(left_edge, direction)),
+            );
+            robot = next;
+        }
+    }
+    boxes.iter().map(|(x, y)| 100 * y + x).sum()
+}
+
+/// Part 1 replays the moves in the warehouse as drawn, part 2 in the twice as wide one.
+impl Visualize for Day15 {
+    fn simulation((warehouse, moves): &Self::Parsed, part: u8) -> Box<dyn Simulation> {
+        let floor = match part {
+            1 => Floor::Narrow(warehouse.clone()),
+            _ => Floor::Wide(Warehouse2::from(warehouse)),
+        };
+        Box::new(Replay {
+            floor,
+            moves: moves.clone(),
+            done: 0,
+        })
+    }
+}
+
+enum Floor {
+    Narrow(Warehouse),
+    Wide(Warehouse2),
+}
+
+/// The robot's moves, one at a time.
+struct Replay {
+    floor: Floor,
+    moves: Vec<Direction>,
+    done: usize,
+}
+
+impl Simulation for Replay {
+    fn frame(&self) -> Frame {
+        match &self.floor {
+            Floor::Narrow(warehouse) => warehouse.frame(),
+            Floor::Wide(warehouse) => warehouse.frame(),
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        let Some(robotmove) = self.moves.get(self.done) else {
+            return false;
+        };
+        let moves = std::slice::from_ref(robotmove);
+        match &mut self.floor {
+            Floor::Narrow(warehouse) => warehouse.move_robot(moves),
+            Floor::Wide(warehouse) => warehouse.move_robot(moves),
+        }
+        self.done += 1;
+        true
+    }
+
+    fn caption(&self) -> String {
+        let sum = match &self.floor {
+            Floor::Narrow(warehouse) => warehouse.sum(),
+            Floor::Wide(warehouse) => warehouse.sum(),
+        };
+        format!("move {} of {}, GPS sum {sum}", self.done, self.moves.len())
+    }
+}
 
 pub fn process_part1(input: &str) -> String {
-    let (input, (mut warehouse, moves)) = parse_input(input).unwrap();
-    assert!(input.is_empty());
-    warehouse.move_robot(moves);
-    warehouse.sum().to_string()
+    common::solve_part1::<Day15>(input)
 }
 
 pub fn process_part2(input: &str) -> String {
-    let new_input = transform_input(input);
-    let input = new_input.as_str();
-    let (input, (mut warehouse, moves)) = parse_input2(input).unwrap();
-    assert!(input.is_empty());
-    warehouse.move_robot(moves);
-    warehouse.sum().to_string()
+    common::solve_part2::<Day15>(input)
+}
+
+pub fn try_process_part1(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part1::<Day15>(input)
+}
+
+pub fn try_process_part2(input: &str) -> Result<String, SolveError> {
+    common::try_solve_part2::<Day15>(input)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -32,6 +217,16 @@ enum Space {
     Wall,
 }
 
+impl Space {
+    fn cell(&self) -> Cell {
+        match self {
+            Space::Empty => Cell::new('.', Rgb::DIM),
+            Space::Box => Cell::new('O', Rgb::HIGHLIGHT),
+            Space::Wall => Cell::new('#', Rgb::WALL),
+        }
+    }
+}
+
 impl From<&Space> for char {
     fn from(val: &Space) -> Self {
         match val {
@@ -50,6 +245,17 @@ enum Space2 {
     Wall,
 }
 
+impl Space2 {
+    fn cell(&self) -> Cell {
+        match self {
+            Space2::Empty => Cell::new('.', Rgb::DIM),
+            Space2::Wall => Cell::new('#', Rgb::WALL),
+            Space2::LeftBox => Cell::new('[', Rgb::HIGHLIGHT),
+            Space2::RightBox => Cell::new(']', Rgb::HIGHLIGHT),
+        }
+    }
+}
+
 impl From<&Space2> for char {
     fn from(val: &Space2) -> Self {
         match val {
@@ -61,21 +267,19 @@ impl From<&Space2> for char {
     }
 }
 
-struct Robot {
-    x: usize,
-    y: usize,
-}
-
-struct Warehouse {
-    contents: Vec<Vec<Space>>,
-    robot: Robot,
+#[derive(Clone)]
+pub struct Warehouse {
+    contents: Grid<Space>,
+    robot: Point,
 }
 
 impl Warehouse {
-    fn move_robot(&mut self, moves: Vec<RobotMove>) {
-        for robotmove in moves.into_iter() {
-            let (new_x, new_y) = Self::next_pos(self.robot.x, self.robot.y, robotmove);
-            match self.contents[new_y][new_x] {
+    fn move_robot(&mut self, moves: &[Direction]) {
+        for &robotmove in moves {
+            let Some((new_x, new_y)) = self.next_pos(self.robot.x, self.robot.y, robotmove) else {
+                continue;
+            };
+            match self.contents[(new_x, new_y)] {
                 Space::Empty => {
                     (self.robot.x, self.robot.y) = (new_x, new_y);
                 }
@@ -89,21 +293,22 @@ impl Warehouse {
         }
     }
 
-    fn move_box(&mut self, x: usize, y: usize, robotmove: RobotMove) -> bool {
-        assert!(y < self.contents.len());
-        assert!(x < self.contents[0].len());
-        assert!(matches!(self.contents[y][x], Space::Box));
-        let (next_x, next_y) = Self::next_pos(x, y, robotmove);
-        match self.contents[next_y][next_x] {
+    fn move_box(&mut self, x: usize, y: usize, robotmove: Direction) -> bool {
+        assert!(self.contents.contains((x, y)));
+        assert!(matches!(self.contents[(x, y)], Space::Box));
+        let Some((next_x, next_y)) = self.next_pos(x, y, robotmove) else {
+            return false;
+        };
+        match self.contents[(next_x, next_y)] {
             Space::Empty => {
-                self.contents[next_y][next_x] = Space::Box;
-                self.contents[y][x] = Space::Empty;
+                self.contents[(next_x, next_y)] = Space::Box;
+                self.contents[(x, y)] = Space::Empty;
                 true
             }
             Space::Box => {
                 if self.move_box(next_x, next_y, robotmove) {
-                    self.contents[next_y][next_x] = Space::Box;
-                    self.contents[y][x] = Space::Empty;
+                    self.contents[(next_x, next_y)] = Space::Box;
+                    self.contents[(x, y)] = Space::Empty;
                     true
                 } else {
                     false
@@ -113,25 +318,23 @@ impl Warehouse {
         }
     }
 
-    fn next_pos(x: usize, y: usize, robotmove: RobotMove) -> (usize, usize) {
-        match robotmove {
-            RobotMove::Up => (x, y - 1),
-            RobotMove::Down => (x, y + 1),
-            RobotMove::Left => (x - 1, y),
-            RobotMove::Right => (x + 1, y),
-        }
+    fn next_pos(&self, x: usize, y: usize, robotmove: Direction) -> Option<(usize, usize)> {
+        self.contents
+            .step(Point::new(x, y), robotmove)
+            .map(Into::into)
+    }
+    fn frame(&self) -> Frame {
+        let mut frame = self.contents.map(Space::cell);
+        frame[self.robot] = Cell::new('@', Rgb::ACTOR);
+        frame
     }
+
     fn sum(&self) -> usize {
         self.contents
             .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter_map(move |(x, space)| match space {
-                        Space::Box => Some(100 * y + x),
-                        _ => None,
-                    })
+            .filter_map(|((x, y), space)| match space {
+                Space::Box => Some(100 * y + x),
+                _ => None,
             })
             .sum()
     }
@@ -139,36 +342,46 @@ impl Warehouse {
 
 impl Debug for Warehouse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        self.contents.iter().enumerate().for_each(|(y, row)| {
-            if y == self.robot.y {
-                row.iter().enumerate().for_each(|(x, space)| {
-                    if x == self.robot.x {
-                        s.push('@');
-                    } else {
-                        s.push(space.into());
-                    }
-                });
-            } else {
-                row.iter().for_each(|space| s.push(space.into()));
-            }
-            s.push('\n');
-        });
-
-        f.write_str(&s)
+        f.write_str(&visualize::text(&self.frame()))
     }
 }
 
 struct Warehouse2 {
-    contents: Vec<Vec<Space2>>,
-    robot: Robot,
+    contents: Grid<Space2>,
+    robot: Point,
+}
+
+impl From<&Warehouse> for Warehouse2 {
+    fn from(warehouse: &Warehouse) -> Self {
+        let cells = warehouse
+            .contents
+            .cells()
+            .flat_map(|space| match space {
+                Space::Empty => [Space2::Empty, Space2::Empty],
+                Space::Box => [Space2::LeftBox, Space2::RightBox],
+                Space::Wall => [Space2::Wall, Space2::Wall],
+            })
+            .collect();
+        let contents = Grid::from_vec(
+            warehouse.contents.width() * 2,
+            warehouse.contents.height(),
+            cells,
+        );
+        let robot = Point {
+            x: warehouse.robot.x * 2,
+            y: warehouse.robot.y,
+        };
+        Self { contents, robot }
+    }
 }
 
 impl Warehouse2 {
-    fn move_robot(&mut self, moves: Vec<RobotMove>) {
-        for robotmove in moves.into_iter() {
-            let (new_x, new_y) = Self::next_pos(self.robot.x, self.robot.y, robotmove);
-            match self.contents[new_y][new_x] {
+    fn move_robot(&mut self, moves: &[Direction]) {
+        for &robotmove in moves {
+            let Some((new_x, new_y)) = self.next_pos(self.robot.x, self.robot.y, robotmove) else {
+                continue;
+            };
+            match self.contents[(new_x, new_y)] {
                 Space2::Empty => {
                     (self.robot.x, self.robot.y) = (new_x, new_y);
                 }
@@ -183,18 +396,17 @@ impl Warehouse2 {
         }
     }
 
-    fn move_box(&mut self, x: usize, y: usize, robotmove: RobotMove, dry_run: bool) -> bool {
-        assert!(y < self.contents.len());
-        assert!(x < self.contents[0].len());
-        match self.contents[y][x] {
+    fn move_box(&mut self, x: usize, y: usize, robotmove: Direction, dry_run: bool) -> bool {
+        assert!(self.contents.contains((x, y)));
+        match self.contents[(x, y)] {
             Space2::LeftBox => {
                 if !self.move_other_box(x + 1, y, robotmove, dry_run) {
                     return false;
                 }
-                if matches!(robotmove, RobotMove::Right) {
+                if matches!(robotmove, Direction::Right) {
                     if !dry_run {
-                        self.contents[y][x + 1] = Space2::LeftBox;
-                        self.contents[y][x] = Space2::Empty;
+                        self.contents[(x + 1, y)] = Space2::LeftBox;
+                        self.contents[(x, y)] = Space2::Empty;
                     }
                     return true;
                 }
@@ -203,30 +415,32 @@ impl Warehouse2 {
                 if !self.move_other_box(x - 1, y, robotmove, dry_run) {
                     return false;
                 }
-                if matches!(robotmove, RobotMove::Left) {
+                if matches!(robotmove, Direction::Left) {
                     if !dry_run {
-                        self.contents[y][x - 1] = Space2::RightBox;
-                        self.contents[y][x] = Space2::Empty;
+                        self.contents[(x - 1, y)] = Space2::RightBox;
+                        self.contents[(x, y)] = Space2::Empty;
                     }
                     return true;
                 }
             }
             x => panic!("move_box called on {x:?}"),
         }
-        let (next_x, next_y) = Self::next_pos(x, y, robotmove);
-        match self.contents[next_y][next_x] {
+        let Some((next_x, next_y)) = self.next_pos(x, y, robotmove) else {
+            return false;
+        };
+        match self.contents[(next_x, next_y)] {
             Space2::Empty => {
                 if !dry_run {
-                    self.contents[next_y][next_x] = self.contents[y][x];
-                    self.contents[y][x] = Space2::Empty;
+                    self.contents[(next_x, next_y)] = self.contents[(x, y)];
+                    self.contents[(x, y)] = Space2::Empty;
                 }
                 true
             }
             Space2::LeftBox | Space2::RightBox => {
                 if self.move_box(next_x, next_y, robotmove, dry_run) {
                     if !dry_run {
-                        self.contents[next_y][next_x] = self.contents[y][x];
-                        self.contents[y][x] = Space2::Empty;
+                        self.contents[(next_x, next_y)] = self.contents[(x, y)];
+                        self.contents[(x, y)] = Space2::Empty;
                     }
                     true
                 } else {
@@ -237,27 +451,28 @@ impl Warehouse2 {
         }
     }
 
-    fn move_other_box(&mut self, x: usize, y: usize, robotmove: RobotMove, dry_run: bool) -> bool {
-        assert!(y < self.contents.len());
-        assert!(x < self.contents[0].len());
+    fn move_other_box(&mut self, x: usize, y: usize, robotmove: Direction, dry_run: bool) -> bool {
+        assert!(self.contents.contains((x, y)));
         assert!(matches!(
-            self.contents[y][x],
+            self.contents[(x, y)],
             Space2::LeftBox | Space2::RightBox
         ));
-        let (next_x, next_y) = Self::next_pos(x, y, robotmove);
-        match self.contents[next_y][next_x] {
+        let Some((next_x, next_y)) = self.next_pos(x, y, robotmove) else {
+            return false;
+        };
+        match self.contents[(next_x, next_y)] {
             Space2::Empty => {
                 if !dry_run {
-                    self.contents[next_y][next_x] = self.contents[y][x];
-                    self.contents[y][x] = Space2::Empty;
+                    self.contents[(next_x, next_y)] = self.contents[(x, y)];
+                    self.contents[(x, y)] = Space2::Empty;
                 }
                 true
             }
             Space2::LeftBox | Space2::RightBox => {
                 if self.move_box(next_x, next_y, robotmove, dry_run) {
                     if !dry_run {
-                        self.contents[next_y][next_x] = self.contents[y][x];
-                        self.contents[y][x] = Space2::Empty;
+                        self.contents[(next_x, next_y)] = self.contents[(x, y)];
+                        self.contents[(x, y)] = Space2::Empty;
                     }
                     true
                 } else {
@@ -268,192 +483,116 @@ impl Warehouse2 {
         }
     }
 
-    fn next_pos(x: usize, y: usize, robotmove: RobotMove) -> (usize, usize) {
-        match robotmove {
-            RobotMove::Up => (x, y - 1),
-            RobotMove::Down => (x, y + 1),
-            RobotMove::Left => (x - 1, y),
-            RobotMove::Right => (x + 1, y),
-        }
+    fn next_pos(&self, x: usize, y: usize, robotmove: Direction) -> Option<(usize, usize)> {
+        self.contents
+            .step(Point::new(x, y), robotmove)
+            .map(Into::into)
+    }
+
+    fn frame(&self) -> Frame {
+        let mut frame = self.contents.map(Space2::cell);
+        frame[self.robot] = Cell::new('@', Rgb::ACTOR);
+        frame
     }
 
     fn sum(&self) -> usize {
         self.contents
             .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter_map(move |(x, space)| match space {
-                        Space2::LeftBox => Some(100 * y + x),
-                        _ => None,
-                    })
+            .filter_map(|((x, y), space)| match space {
+                Space2::LeftBox => Some(100 * y + x),
+                _ => None,
             })
             .sum()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum RobotMove {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl From<char> for RobotMove {
-    fn from(value: char) -> Self {
-        match value {
-            '^' => Self::Up,
-            'v' => Self::Down,
-            '<' => Self::Left,
-            '>' => Self::Right,
-            c => panic!("Invalid character for RobotMove: {c}"),
-        }
-    }
-}
-
-fn parse_input(input: &str) -> IResult<&str, (Warehouse, Vec<RobotMove>)> {
-    let (input, warehouse) = parse_warehouse(input)?;
-    let (input, moves) = parse_moves(input)?;
-    Ok((input, (warehouse, moves)))
-}
-
-fn parse_input2(input: &str) -> IResult<&str, (Warehouse2, Vec<RobotMove>)> {
-    let (input, warehouse) = parse_warehouse2(input)?;
-    let (input, moves) = parse_moves(input)?;
-    Ok((input, (warehouse, moves)))
-}
-
-fn parse_warehouse(input: &str) -> IResult<&str, Warehouse> {
-    let (input, warehouse_chars) =
-        terminated(separated_list1(newline, many1(one_of(".O#@"))), tag("\n\n"))(input)?;
-    let mut robot: Option<Robot> = None;
-    let mut contents = Vec::new();
-    warehouse_chars
-        .into_iter()
-        .enumerate()
-        .for_each(|(y, row)| {
-            let mut warehouse_row = Vec::new();
-            row.into_iter().enumerate().for_each(|(x, c)| match c {
-                '.' => warehouse_row.push(Space::Empty),
-                'O' => warehouse_row.push(Space::Box),
-                '#' => warehouse_row.push(Space::Wall),
-                '@' => {
-                    warehouse_row.push(Space::Empty);
-                    robot.replace(Robot { x, y });
-                }
-                other => panic!("Unexpecter character in warehouse: {other}"),
-            });
-            contents.push(warehouse_row);
-        });
-    let robot = robot.expect("Robot missing!");
-    let warehouse = Warehouse { contents, robot };
-
-    Ok((input, warehouse))
-}
-
-fn parse_warehouse2(input: &str) -> IResult<&str, Warehouse2> {
-    let (input, warehouse_chars) = terminated(
-        separated_list1(newline, many1(one_of(".[]#@"))),
-        tag("\n\n"),
-    )(input)?;
-    let mut robot: Option<Robot> = None;
-    let mut contents = Vec::new();
-    warehouse_chars
-        .into_iter()
-        .enumerate()
-        .for_each(|(y, row)| {
-            let mut warehouse_row = Vec::new();
-            row.into_iter().enumerate().for_each(|(x, c)| match c {
-                '.' => warehouse_row.push(Space2::Empty),
-                '[' => warehouse_row.push(Space2::LeftBox),
-                ']' => warehouse_row.push(Space2::RightBox),
-                '#' => warehouse_row.push(Space2::Wall),
-                '@' => {
-                    warehouse_row.push(Space2::Empty);
-                    robot.replace(Robot { x, y });
-                }
-                other => panic!("Unexpecter character in warehouse: {other}"),
-            });
-            contents.push(warehouse_row);
-        });
-    let robot = robot.expect("Robot missing!");
-    let warehouse = Warehouse2 { contents, robot };
-
-    Ok((input, warehouse))
-}
-
-fn parse_moves(input: &str) -> IResult<&str, Vec<RobotMove>> {
-    let mut moves = Vec::new();
-    for c in input.chars() {
-        match c {
-            '\n' => continue,
-            '^' | 'v' | '<' | '>' => {
-                moves.push(c.into());
-            }
-            _ => {
-                return Err(nom::Err::Error(make_error(
-                    input,
-                    nom::error::ErrorKind::Fail,
-                )))
-            }
-        }
+fn parse_warehouse(input: &str) -> Result<Warehouse, ParseError> {
+    let contents = parsing::grid(input, |c| match c {
+        '.' | '@' => Some(Space::Empty),
+        'O' => Some(Space::Box),
+        '#' => Some(Space::Wall),
+        _ => None,
+    })?;
+    let robot = parsing::find(input, '@')
+        .map(|(x, y)| Point { x, y })
+        .ok_or_else(|| ParseError::at(input, input, "a robot `@` in the warehouse"))?;
+    if let Some((second, _)) = input.match_indices('@').nth(1) {
+        return Err(ParseError::at(
+            input,
+            &input[second..],
+            "only one robot `@`",
+        ));
     }
-    Ok(("", moves))
+    Ok(Warehouse { contents, robot })
 }
 
-fn transform_input(input: &str) -> String {
-    let mut output = String::new();
-    input.chars().for_each(|c| {
-        let out = match c {
-            '.' => "..",
-            '#' => "##",
-            '@' => "@.",
-            'O' => "[]",
-            '\n' => "\n",
-            '^' => "^",
-            'v' => "v",
-            '<' => "<",
-            '>' => ">",
-            x => panic!("Unexpected character in input: {x}"),
-        };
-        output.push_str(out);
-    });
-
-    output
+fn parse_moves(input: &str, moves: &str) -> Result<Vec<Direction>, ParseError> {
+    moves
+        .char_indices()
+        .filter(|&(_, c)| c != '\n')
+        .map(|(i, c)| {
+            Direction::from_arrow(c)
+                .ok_or_else(|| ParseError::at(input, &moves[i..], "a robot move"))
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+
+    common::day_tests!(Day15);
 
     #[test]
-    fn part1_1() {
-        let input = fs::read_to_string("./part1-example1.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "2028");
+    fn missing_robot() {
+        let Err(SolveError::Parse(error)) = try_process_part1("####\n#..#\n####\n\n<>") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.expected, "a robot `@` in the warehouse");
     }
 
     #[test]
-    fn part1_2() {
-        let input = fs::read_to_string("./part1-example2.txt").unwrap();
-        let result = process_part1(&input);
-        assert_eq!(result, "10092");
+    fn warehouses_without_walls() {
+        for input in ["@O\n\n>", "O@\n\n<<", "O.\n@.\n\n^^>^", ".O\nO@\n\n<^<v"] {
+            let parsed = Day15::parse(input).unwrap();
+            assert_eq!(
+                Day15::part1(&parsed),
+                Day15::reference_part1(&parsed),
+                "{input}"
+            );
+            assert_eq!(
+                Day15::part2(&parsed),
+                Day15::reference_part2(&parsed),
+                "{input}"
+            );
+        }
+        assert_eq!(process_part1("@O\n\n>"), "1");
+        assert_eq!(process_part2("@O\n\n>"), "2");
     }
 
     #[test]
-    fn part2_1() {
-        let input = fs::read_to_string("./part2-example1.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "618");
+    fn several_robots() {
+        let Err(SolveError::Parse(error)) = try_process_part1("#####\n#@.@#\n#####\n\n>") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "only one robot `@`");
     }
 
     #[test]
-    fn part2_2() {
-        let input = fs::read_to_string("./part1-example2.txt").unwrap();
-        let result = process_part2(&input);
-        assert_eq!(result, "9021");
+    fn replays_the_moves() {
+        let input = "#####\n#@O.#\n#####\n\n>>";
+        let mut replay = visualize::simulate::<Day15>(input, 1).unwrap();
+        assert_eq!(visualize::text(&replay.frame()), "#####\n#@O.#\n#####\n");
+        assert_eq!(visualize::skip(replay.as_mut(), 5), 2);
+        assert_eq!(visualize::text(&replay.frame()), "#####\n#.@O#\n#####\n");
+        assert_eq!(replay.caption(), "move 2 of 2, GPS sum 103");
+        let mut replay = visualize::simulate::<Day15>(input, 2).unwrap();
+        visualize::skip(replay.as_mut(), 5);
+        assert_eq!(
+            visualize::text(&replay.frame()),
+            "##########\n##..@[].##\n##########\n"
+        );
     }
 }
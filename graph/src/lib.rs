@@ -0,0 +1,13 @@
+//! Graph algorithms over implicit graphs: rather than building an adjacency list, callers pass a
+//! function from a node to its neighbours, so a grid, a rule table or a puzzle state can be
+//! searched as it is.
+
+mod disjoint_set;
+mod order;
+mod paths;
+mod search;
+
+pub use disjoint_set::DisjointSet;
+pub use order::{strongly_connected_components, topological_sort, Cycle};
+pub use paths::{astar, dijkstra, distances};
+pub use search::{bfs, count_paths, dfs, Bfs, Dfs};
@@ -0,0 +1,185 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+/// The nodes that [`topological_sort`] could not put in order, because each of them is on a
+/// cycle or comes after one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+/// Orders `nodes` so that every node comes before its successors, keeping the given order
+/// where that leaves a choice. Successors outside `nodes` are left out, so a subset can be put in
+/// the order of a larger graph.
+pub fn topological_sort<N, I>(
+    nodes: &[N],
+    mut successors: impl FnMut(&N) -> I,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut positions = HashMap::<&N, Vec<usize>>::new();
+    for (i, node) in nodes.iter().enumerate() {
+        positions.entry(node).or_default().push(i);
+    }
+    let mut edges = vec![Vec::new(); nodes.len()];
+    let mut before = vec![0; nodes.len()];
+    for (i, node) in nodes.iter().enumerate() {
+        for next in successors(node) {
+            for &j in positions.get(&next).into_iter().flatten() {
+                edges[i].push(j);
+                before[j] += 1;
+            }
+        }
+    }
+    let mut ready = (0..nodes.len())
+        .filter(|&i| before[i] == 0)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(Reverse(i)) = ready.pop() {
+        order.push(nodes[i].clone());
+        for &j in &edges[i] {
+            before[j] -= 1;
+            if before[j] == 0 {
+                ready.push(Reverse(j));
+            }
+        }
+    }
+    if order.len() == nodes.len() {
+        Ok(order)
+    } else {
+        let left = (0..nodes.len()).filter(|&i| before[i] > 0);
+        Err(Cycle(left.map(|i| nodes[i].clone()).collect()))
+    }
+}
+
+/// Splits the graph of `nodes` into groups that can each reach every node in the group, by
+/// Tarjan's algorithm. A group only comes after the groups it can reach. Successors outside
+/// `nodes` are left out.
+pub fn strongly_connected_components<N, I>(
+    nodes: &[N],
+    mut successors: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let numbers = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node, i))
+        .collect::<HashMap<_, _>>();
+    let edges = nodes
+        .iter()
+        .map(|node| {
+            successors(node)
+                .into_iter()
+                .filter_map(|next| numbers.get(&next).copied())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // the order each node was first met in, and the earliest node its subtree gets back to
+    let mut met = vec![None; nodes.len()];
+    let mut low = vec![0; nodes.len()];
+    let mut on_stack = vec![false; nodes.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut count = 0;
+    for root in 0..nodes.len() {
+        if met[root].is_some() {
+            continue;
+        }
+        // each frame is a node and how many of its edges have been followed
+        let mut walk = vec![(root, 0)];
+        while let Some(&mut (node, ref mut edge)) = walk.last_mut() {
+            if *edge == 0 {
+                met[node] = Some(count);
+                low[node] = count;
+                count += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+            if let Some(&next) = edges[node].get(*edge) {
+                *edge += 1;
+                match met[next] {
+                    None => walk.push((next, 0)),
+                    Some(order) if on_stack[next] => low[node] = low[node].min(order),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            walk.pop();
+            if let Some(&(parent, _)) = walk.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if Some(low[node]) == met[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(nodes[member].clone());
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_topologically() {
+        let after = |&n: &u32| (1..=12).filter(move |m| m % n == 0 && *m != n);
+        let order = topological_sort(&[12, 6, 4, 3, 2, 1], after).unwrap();
+        assert_eq!(order, [1, 3, 2, 6, 4, 12]);
+        assert_eq!(topological_sort(&[5, 7], after), Ok(vec![5, 7]));
+        let order = topological_sort(&[3, 3, 1], |&n| [n + 1, n + 2]).unwrap();
+        assert_eq!(order, [1, 3, 3]);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let next = |&n: &u32| [(n + 1) % 3, 4];
+        assert_eq!(
+            topological_sort(&[0, 1, 2, 3, 4], next),
+            Err(Cycle(vec![0, 1, 2, 4]))
+        );
+        assert!(topological_sort(&[1, 1], |&n| [n]).is_err());
+    }
+
+    #[test]
+    fn finds_components() {
+        let edges = |&n: &char| match n {
+            'a' => vec!['b'],
+            'b' => vec!['c', 'e'],
+            'c' => vec!['a', 'd'],
+            'd' => vec!['d'],
+            'e' => vec!['f'],
+            'f' => vec!['e', 'z'],
+            _ => vec![],
+        };
+        let mut components = strongly_connected_components(&['a', 'b', 'c', 'd', 'e', 'f'], edges);
+        for component in &mut components {
+            component.sort_unstable();
+        }
+        assert_eq!(components, [vec!['d'], vec!['e', 'f'], vec!['a', 'b', 'c']]);
+    }
+
+    #[test]
+    fn deep_components() {
+        let nodes = (0..100_000).collect::<Vec<u32>>();
+        let components = strongly_connected_components(&nodes, |&n| [(n + 1) % 100_000]);
+        assert_eq!(components.len(), 1);
+        let components = strongly_connected_components(&nodes, |&n| [n + 1]);
+        assert_eq!(components.len(), 100_000);
+        assert_eq!(components[0], [99_999]);
+    }
+}
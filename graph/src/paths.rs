@@ -0,0 +1,152 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The cheapest path from `start` to a goal, with its cost, where `neighbours` gives each node's
+/// neighbours with the cost of the step to them. `None` if no goal can be reached.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], heading first where `heuristic` says a goal is nearest. The path is only
+/// guaranteed to be the cheapest if `heuristic` never overestimates the cost to a goal.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // nodes are numbered as they are met, so the queue and the paths only hold numbers
+    let mut nodes = vec![start.clone()];
+    let mut numbers = HashMap::from([(start.clone(), 0)]);
+    let mut costs = vec![C::default()];
+    let mut previous: Vec<Option<usize>> = vec![None];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    while let Some(Reverse((_, cost, number))) = queue.pop() {
+        if cost > costs[number] {
+            continue;
+        }
+        if is_goal(&nodes[number]) {
+            let mut path = vec![nodes[number].clone()];
+            let mut number = number;
+            while let Some(before) = previous[number] {
+                path.push(nodes[before].clone());
+                number = before;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+        for (next, step) in neighbours(&nodes[number]) {
+            let next_cost = cost + step;
+            let next_number = match numbers.entry(next) {
+                Entry::Occupied(entry) if costs[*entry.get()] <= next_cost => continue,
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    nodes.push(entry.key().clone());
+                    costs.push(next_cost);
+                    previous.push(None);
+                    *entry.insert(nodes.len() - 1)
+                }
+            };
+            costs[next_number] = next_cost;
+            previous[next_number] = Some(number);
+            let estimate = next_cost + heuristic(&nodes[next_number]);
+            queue.push(Reverse((estimate, next_cost, next_number)));
+        }
+    }
+    None
+}
+
+/// The cost of the cheapest path from `start` to every node it can reach.
+pub fn distances<N, C, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> HashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::new();
+    let mut queue = BinaryHeap::from([(Reverse(C::default()), 0)]);
+    let mut waiting = vec![Some(start)];
+    while let Some((Reverse(cost), number)) = queue.pop() {
+        let Some(node) = waiting[number].take() else {
+            continue;
+        };
+        if costs.contains_key(&node) {
+            continue;
+        }
+        for (next, step) in neighbours(&node) {
+            if !costs.contains_key(&next) {
+                waiting.push(Some(next));
+                queue.push((Reverse(cost + step), waiting.len() - 1));
+            }
+        }
+        costs.insert(node, cost);
+    }
+    costs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps of one to `x + y + 1` in a 5 by 5 square, with a wall across the middle row that
+    /// only leaves the rightmost column open.
+    fn steps(&(x, y): &(u32, u32)) -> Vec<((u32, u32), u32)> {
+        let cost = x + y + 1;
+        [
+            (x + 1, y),
+            (x, y + 1),
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
+        ]
+        .into_iter()
+        .filter(|&(x, y)| x < 5 && y < 5 && (y != 2 || x == 4))
+        .map(|next| (next, cost))
+        .collect()
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        let (path, cost) = dijkstra((0, 0), steps, |&pos| pos == (0, 4)).unwrap();
+        assert_eq!(cost, 1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 7 + 6 + 5 + 4);
+        assert_eq!(path.len(), 13);
+        assert_eq!(path[6], (4, 2));
+        let manhattan = |&(x, y): &(u32, u32)| x + 4 - y;
+        let found = astar((0, 0), steps, manhattan, |&pos| pos == (0, 4));
+        assert_eq!(found.map(|(_, cost)| cost), Some(cost));
+        assert_eq!(dijkstra((0, 0), steps, |&pos| pos == (9, 9)), None);
+        assert_eq!(dijkstra(7, |_| [(8, 1)], |&n| n == 7), Some((vec![7], 0)));
+    }
+
+    #[test]
+    fn takes_cheaper_detours() {
+        let edges = |&n: &char| match n {
+            'a' => vec![('b', 10), ('c', 1)],
+            'c' => vec![('d', 1)],
+            'd' => vec![('b', 1)],
+            _ => vec![],
+        };
+        let (path, cost) = dijkstra('a', edges, |&n| n == 'b').unwrap();
+        assert_eq!((path, cost), (vec!['a', 'c', 'd', 'b'], 3));
+        let costs = distances('a', edges);
+        assert_eq!(costs.len(), 4);
+        assert_eq!((costs[&'b'], costs[&'d']), (3, 2));
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Every node reachable from `start`, each once, nearest first, with the number of steps to it.
+pub fn bfs<N, I, F>(start: N, neighbours: F) -> Bfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    Bfs {
+        queue: VecDeque::from([(start.clone(), 0)]),
+        seen: HashSet::from([start]),
+        neighbours,
+    }
+}
+
+/// Every node reachable from `start`, each once, following each path as deep as it goes before
+/// backing up.
+pub fn dfs<N, I, F>(start: N, neighbours: F) -> Dfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    Dfs {
+        stack: vec![start],
+        seen: HashSet::new(),
+        neighbours,
+    }
+}
+
+/// The iterator returned by [`bfs`].
+pub struct Bfs<N, F> {
+    queue: VecDeque<(N, usize)>,
+    seen: HashSet<N>,
+    neighbours: F,
+}
+
+impl<N, I, F> Iterator for Bfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    type Item = (N, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, steps) = self.queue.pop_front()?;
+        for next in (self.neighbours)(&node) {
+            if self.seen.insert(next.clone()) {
+                self.queue.push_back((next, steps + 1));
+            }
+        }
+        Some((node, steps))
+    }
+}
+
+/// The iterator returned by [`dfs`].
+pub struct Dfs<N, F> {
+    stack: Vec<N>,
+    seen: HashSet<N>,
+    neighbours: F,
+}
+
+impl<N, I, F> Iterator for Dfs<N, F>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        loop {
+            let node = self.stack.pop()?;
+            if !self.seen.insert(node.clone()) {
+                continue;
+            }
+            let first = self.stack.len();
+            self.stack.extend(
+                (self.neighbours)(&node)
+                    .into_iter()
+                    .filter(|next| !self.seen.contains(next)),
+            );
+            // visit the neighbours in the order they were given
+            self.stack[first..].reverse();
+            return Some(node);
+        }
+    }
+}
+
+/// The number of distinct paths from `start` to a goal, where a path stops at the first goal it
+/// reaches. Shared parts of paths are only walked once, so the count can be far larger than the
+/// graph. Panics if a cycle can be reached from `start`, since that has endless paths.
+pub fn count_paths<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> usize
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut counts = HashMap::new();
+    let mut walking = HashSet::new();
+    // a node is pushed without its neighbours when first met, and with them once they are counted
+    let mut stack = vec![(start.clone(), None::<Vec<N>>)];
+    while let Some((node, next)) = stack.pop() {
+        match next {
+            Some(next) => {
+                let count = next.iter().map(|next| counts[next]).sum();
+                walking.remove(&node);
+                counts.insert(node, count);
+            }
+            None if counts.contains_key(&node) => {}
+            None if is_goal(&node) => {
+                counts.insert(node, 1);
+            }
+            None => {
+                assert!(walking.insert(node.clone()), "count_paths reached a cycle");
+                let next = neighbours(&node).into_iter().collect::<Vec<_>>();
+                let unknown = next
+                    .iter()
+                    .filter(|next| !counts.contains_key(next))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                stack.push((node, Some(next)));
+                stack.extend(unknown.into_iter().map(|next| (next, None)));
+            }
+        }
+    }
+    counts[&start]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` leads to `2n` and `2n + 1` below 16, a binary tree rooted at 1.
+    fn children(&n: &u32) -> Vec<u32> {
+        [2 * n, 2 * n + 1].into_iter().filter(|&c| c < 16).collect()
+    }
+
+    #[test]
+    fn searches_breadth_first() {
+        let order = bfs(1, children).collect::<Vec<_>>();
+        assert_eq!(order.len(), 15);
+        assert_eq!(order[..4], [(1, 0), (2, 1), (3, 1), (4, 2)]);
+        assert_eq!(order[14], (15, 3));
+        // every other number on a cycle of ten
+        let steps = bfs(0, |&n| [(n + 2) % 10, (n + 8) % 10]).collect::<Vec<_>>();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps.last(), Some(&(6, 2)));
+    }
+
+    #[test]
+    fn searches_depth_first() {
+        let order = dfs(1, children).collect::<Vec<_>>();
+        assert_eq!(order[..5], [1, 2, 4, 8, 9]);
+        assert_eq!(order.len(), 15);
+        let order = dfs(0, |&n| [(n + 1) % 4, (n + 3) % 4]).collect::<Vec<_>>();
+        assert_eq!(order, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn counts_paths() {
+        assert_eq!(count_paths(1, children, |&n| n >= 8), 8);
+        // a grid of 10 by 10 steps right or down, C(20, 10) paths
+        let paths = count_paths(
+            (0, 0),
+            |&(x, y)| {
+                [(x + 1, y), (x, y + 1)]
+                    .into_iter()
+                    .filter(|&(x, y)| x <= 10 && y <= 10)
+            },
+            |&pos| pos == (10, 10),
+        );
+        assert_eq!(paths, 184_756);
+        assert_eq!(count_paths(0, |_| [], |_| false), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn cycles_have_no_path_count() {
+        count_paths(0, |&n| [(n + 1) % 3], |&n| n == 5);
+    }
+}
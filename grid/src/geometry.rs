@@ -0,0 +1,218 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A position on a grid; coordinates never go negative, so moving is checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves by `vector`, returning `None` when a coordinate would become negative.
+    pub fn offset(self, vector: Vector) -> Option<Self> {
+        let x = self.x.checked_add_signed(vector.dx)?;
+        let y = self.y.checked_add_signed(vector.dy)?;
+        Some(Self { x, y })
+    }
+
+    /// Moves by `vector`, returning `None` when that leaves a `width` by `height` area.
+    pub fn offset_within(self, vector: Vector, width: usize, height: usize) -> Option<Self> {
+        self.offset(vector).filter(|p| p.is_within(width, height))
+    }
+
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        self.offset(direction.vector())
+    }
+
+    pub fn step_within(self, direction: Direction, width: usize, height: usize) -> Option<Self> {
+        self.offset_within(direction.vector(), width, height)
+    }
+
+    pub fn is_within(self, width: usize, height: usize) -> bool {
+        self.x < width && self.y < height
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Self) -> Vector {
+        Vector::new(
+            self.x as isize - other.x as isize,
+            self.y as isize - other.y as isize,
+        )
+    }
+}
+
+/// A displacement between two points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Vector {
+    pub const fn new(dx: isize, dy: isize) -> Self {
+        Self { dx, dy }
+    }
+}
+
+impl Add for Vector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.dx - other.dx, self.dy - other.dy)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.dx, -self.dy)
+    }
+}
+
+impl Mul<isize> for Vector {
+    type Output = Self;
+
+    fn mul(self, factor: isize) -> Self {
+        Self::new(self.dx * factor, self.dy * factor)
+    }
+}
+
+impl From<(isize, isize)> for Vector {
+    fn from((dx, dy): (isize, isize)) -> Self {
+        Self { dx, dy }
+    }
+}
+
+/// One of the four orthogonal directions, with `y` growing downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from `Up`.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Right => Self::Up,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Right => Self::Left,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+        }
+    }
+
+    pub fn vector(self) -> Vector {
+        match self {
+            Self::Up => Vector::new(0, -1),
+            Self::Right => Vector::new(1, 0),
+            Self::Down => Vector::new(0, 1),
+            Self::Left => Vector::new(-1, 0),
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    /// Parses the arrow notation `^`, `>`, `v` and `<`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            '>' => Some(Self::Right),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(
+                direction.vector() + direction.opposite().vector(),
+                Vector::default()
+            );
+            assert_eq!(Direction::from_arrow(direction.arrow()), Some(direction));
+        }
+        assert_eq!(Direction::from_arrow('x'), None);
+    }
+
+    #[test]
+    fn checked_steps() {
+        let origin = Point::new(0, 0);
+        assert_eq!(origin.step(Direction::Left), None);
+        assert_eq!(origin.step(Direction::Down), Some(Point::new(0, 1)));
+        assert_eq!(Point::new(2, 1).step_within(Direction::Right, 3, 2), None);
+        assert_eq!(Point::new(3, 4) - Point::new(5, 1), Vector::new(-2, 3));
+        assert_eq!(
+            Point::new(5, 1).offset(Vector::new(-2, 3) * 2),
+            Some(Point::new(1, 7))
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+mod geometry;
+
+pub use geometry::{Direction, Point, Vector};
+
+const OFFSETS8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangular grid stored row by row in a single `Vec`, addressed by `(x, y)`. A grid with no
+/// columns or no rows has no cells, and iterating over its lines gives nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics if `cells` does not hold exactly `width * height` elements.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "Grid size mismatch");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The position of `(x, y)` in the flat storage, as used by [`Grid::position`].
+    pub fn index_of(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+
+    /// Panics if `index` is outside the flat storage.
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        assert!(index < self.len(), "index {index} is outside the grid");
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.index_of(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) {
+            let index = self.index_of(pos);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `pos` by `(dx, dy)`, returning `None` when that leaves the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        self.contains((x, y)).then_some((x, y))
+    }
+
+    /// Moves `point` one step in `direction`, returning `None` when that leaves the grid.
+    pub fn step(&self, point: Point, direction: Direction) -> Option<Point> {
+        point.step_within(direction, self.width, self.height)
+    }
+
+    pub fn get_offset(&self, pos: (usize, usize), delta: (isize, isize)) -> Option<&T> {
+        self.offset(pos, delta).and_then(|pos| self.get(pos))
+    }
+
+    /// The horizontally and vertically adjacent positions inside the grid.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL.into_iter().filter_map(move |direction| {
+            let Vector { dx, dy } = direction.vector();
+            self.offset(pos, (dx, dy))
+        })
+    }
+
+    /// The adjacent positions inside the grid, including diagonals.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS8
+            .into_iter()
+            .filter_map(move |delta| self.offset(pos, delta))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks of 0 are not allowed, but without columns there are no cells to chunk anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Every line running down and to the right, starting from the top row and left column.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (width, height) = self.line_extent();
+        let starts = (0..width)
+            .rev()
+            .map(|x| (x, 0))
+            .chain((1..height).map(|y| (0, y)));
+        starts.map(move |start| self.line(start, (1, 1)))
+    }
+
+    /// Every line running down and to the left, starting from the top row and right column.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (width, height) = self.line_extent();
+        let starts = (0..width)
+            .map(|x| (x, 0))
+            .chain((1..height).map(move |y| (width - 1, y)));
+        starts.map(move |start| self.line(start, (-1, 1)))
+    }
+
+    /// The width and height to start diagonals from, both 0 if there are no cells to start at.
+    fn line_extent(&self) -> (usize, usize) {
+        if self.is_empty() {
+            (0, 0)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    fn line(&self, start: (usize, usize), delta: (isize, isize)) -> impl Iterator<Item = &T> {
+        std::iter::successors(Some(start), move |&pos| self.offset(pos, delta))
+            .map(|pos| &self[pos])
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        &self.cells[self.index_of(pos)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        assert!(self.contains(pos), "{pos:?} is outside the grid");
+        let index = self.index_of(pos);
+        &mut self.cells[index]
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        &self[(point.x, point.y)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        &mut self[(point.x, point.y)]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::from_vec(3, 2, "abcdef".chars().collect())
+    }
+
+    #[test]
+    fn index_and_display() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], 'e');
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn offsets_stay_inside() {
+        let grid = sample();
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((2, 1), (1, 0)), None);
+        assert_eq!(grid.get_offset((1, 0), (1, 1)), Some(&'f'));
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn lines() {
+        let grid = sample();
+        let collect = |line: &mut dyn Iterator<Item = &char>| line.collect::<String>();
+        let columns = grid
+            .columns()
+            .map(|mut c| collect(&mut c))
+            .collect::<Vec<_>>();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+        let diagonals = grid
+            .diagonals()
+            .map(|mut d| collect(&mut d))
+            .collect::<Vec<_>>();
+        assert_eq!(diagonals, ["c", "bf", "ae", "d"]);
+        let anti = grid
+            .anti_diagonals()
+            .map(|mut d| collect(&mut d))
+            .collect::<Vec<_>>();
+        assert_eq!(anti, ["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn empty_grids() {
+        for grid in [
+            Grid::<char>::from_vec(0, 3, vec![]),
+            Grid::from_vec(3, 0, vec![]),
+        ] {
+            assert!(grid.is_empty());
+            assert_eq!(grid.rows().count(), 0);
+            assert_eq!(grid.column(0).count(), 0);
+            assert_eq!(grid.diagonals().count(), 0);
+            assert_eq!(grid.anti_diagonals().count(), 0);
+            assert_eq!(grid.positions().count(), 0);
+            assert_eq!(grid.get((0, 0)), None);
+            assert_eq!(grid.to_string(), "");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the grid")]
+    fn positions_need_a_cell() {
+        Grid::<char>::from_vec(0, 3, vec![]).position(0);
+    }
+}
@@ -0,0 +1,71 @@
+use common::ParseError;
+use grid::Grid;
+
+/// Parses one row per line, mapping every character to a cell; all rows must be equally wide.
+pub fn grid<T>(
+    input: &str,
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> Result<Grid<T>, ParseError> {
+    let mut width = None;
+    let mut height = 0;
+    let mut cells = Vec::with_capacity(input.len());
+    for row in input.lines() {
+        let mut row_width = 0;
+        for (i, c) in row.char_indices() {
+            let value = cell(c).ok_or_else(|| ParseError::at(input, &row[i..], "a grid cell"))?;
+            cells.push(value);
+            row_width += 1;
+        }
+        match width {
+            None if row_width == 0 => {
+                return Err(ParseError::at(input, row, "a non-empty row"));
+            }
+            None => width = Some(row_width),
+            Some(width) if width != row_width => {
+                return Err(ParseError::at(
+                    input,
+                    row,
+                    format!("a row of width {width}"),
+                ));
+            }
+            Some(_) => {}
+        }
+        height += 1;
+    }
+    let width = width.ok_or_else(|| ParseError::at(input, input, "at least one row"))?;
+    Ok(Grid::from_vec(width, height, cells))
+}
+
+/// The `(x, y)` of the first `c` in a grid of one row per line, counting bytes along the row.
+pub fn find(input: &str, c: char) -> Option<(usize, usize)> {
+    input
+        .lines()
+        .enumerate()
+        .find_map(|(y, row)| row.find(c).map(|x| (x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid("abc\ndef", Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], 'e');
+        assert_eq!(find("abc\ndef", 'e'), Some((1, 1)));
+        assert_eq!(find("abc\ndef", 'x'), None);
+    }
+
+    #[test]
+    fn grid_errors() {
+        let error = grid("ab\nabc", Some).unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a row of width 2")
+        );
+        let error = grid("a.\n.x", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert!(grid("", Some).is_err());
+    }
+}
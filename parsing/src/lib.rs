@@ -0,0 +1,116 @@
+use common::{IResult, ParseError};
+use nom::{
+    character::complete::{newline, space1},
+    combinator::cut,
+    error::VerboseError,
+    multi::separated_list1,
+    sequence::{pair, terminated},
+    Parser,
+};
+
+mod grids;
+mod numbers;
+
+pub use grids::{find, grid};
+pub use numbers::{integers, signed, unsigned, Signed, Unsigned};
+
+/// At least one `item`, with a `separator` between each two. Once a separator has been read, an
+/// `item` has to follow: a malformed one is reported there rather than ending the list.
+pub fn separated<'a, S, O>(
+    separator: impl Parser<&'a str, S, VerboseError<&'a str>>,
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(separator, cut(item))
+}
+
+/// One `item` per line, at least one, with a malformed line reported where it goes wrong.
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated(newline, item)
+}
+
+/// One `item` per line up to and including the blank line that ends the section. A malformed
+/// line is reported as a missing blank line.
+pub fn section<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    terminated(separated_list1(newline, item), pair(newline, newline))
+}
+
+/// Blocks of lines with a blank line between each two, each one an `item`.
+pub fn blocks<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated(pair(newline, newline), item)
+}
+
+/// At least one `item`, separated by spaces or tabs.
+pub fn spaced<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(space1, item)
+}
+
+/// Splits `input` at its first blank line, into the section before it and everything after it.
+/// Without a blank line, the error says that one was expected before `next`.
+pub fn split_sections<'a>(input: &'a str, next: &str) -> Result<(&'a str, &'a str), ParseError> {
+    input.split_once("\n\n").ok_or_else(|| {
+        ParseError::at(
+            input,
+            &input[input.len()..],
+            format!("a blank line before {next}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::{alpha1, char as nomchar};
+
+    fn pair_of_numbers(input: &str) -> IResult<&str, (u32, u32)> {
+        let (input, a) = unsigned(input)?;
+        let (input, _) = nomchar(',')(input)?;
+        let (input, b) = unsigned(input)?;
+        Ok((input, (a, b)))
+    }
+
+    #[test]
+    fn parses_lines() {
+        let input = "1,2\n3,4";
+        let parsed = common::finish(input, lines(pair_of_numbers)(input));
+        assert_eq!(parsed, Ok(vec![(1, 2), (3, 4)]));
+        let input = "1,2\n3;4";
+        let error = common::finish(input, lines(pair_of_numbers)(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "`,`");
+    }
+
+    #[test]
+    fn parses_sections_and_blocks() {
+        let input = "a b\nc\n\nd\n\nf";
+        let (rest, first) = section(spaced(alpha1))(input).unwrap();
+        assert_eq!(first, [vec!["a", "b"], vec!["c"]]);
+        assert_eq!(
+            common::finish(rest, blocks(alpha1)(rest)),
+            Ok(vec!["d", "f"])
+        );
+        let input = "d\n\n\nf";
+        let error = common::finish(input, blocks(alpha1)(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.expected, "letters");
+        let input = "a\nb\nc";
+        let error = common::finish(input, section(alpha1)(input)).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(error.expected, "a line break");
+    }
+
+    #[test]
+    fn splits_sections() {
+        assert_eq!(split_sections("a\n\nb\n\nc", "c"), Ok(("a", "b\n\nc")));
+        let error = split_sections("a\nb", "the moves").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "a blank line before the moves");
+    }
+}
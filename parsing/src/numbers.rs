@@ -0,0 +1,109 @@
+use common::IResult;
+use nom::{
+    character::complete as nomchar,
+    error::{ErrorKind, ParseError},
+};
+
+/// An integer type without sign, parsed from its decimal digits.
+pub trait Unsigned: Sized {
+    fn parse(input: &str) -> IResult<&str, Self>;
+}
+
+/// An integer type with sign, parsed from its decimal digits after an optional `-` or `+`.
+pub trait Signed: Sized {
+    fn parse(input: &str) -> IResult<&str, Self>;
+}
+
+macro_rules! impl_integers {
+    ($trait:ident: $($t:ident)+) => {
+        $(
+            impl $trait for $t {
+                fn parse(input: &str) -> IResult<&str, Self> {
+                    nomchar::$t(input)
+                }
+            }
+        )+
+    };
+}
+
+impl_integers!(Unsigned: u8 u16 u32 u64 u128);
+impl_integers!(Signed: i8 i16 i32 i64 i128);
+
+impl Unsigned for usize {
+    fn parse(input: &str) -> IResult<&str, Self> {
+        let (rest, n) = nomchar::u64(input)?;
+        let n = usize::try_from(n).map_err(|_| digit_error(input))?;
+        Ok((rest, n))
+    }
+}
+
+fn digit_error(input: &str) -> nom::Err<nom::error::VerboseError<&str>> {
+    nom::Err::Error(ParseError::from_error_kind(input, ErrorKind::Digit))
+}
+
+/// A number without sign. One too large for `T` is an error, expecting a number.
+pub fn unsigned<T: Unsigned>(input: &str) -> IResult<&str, T> {
+    T::parse(input)
+}
+
+/// A number with an optional sign. One out of range for `T` is an error, expecting a number.
+pub fn signed<T: Signed>(input: &str) -> IResult<&str, T> {
+    T::parse(input)
+}
+
+/// Every number on the rest of the line, skipping whatever is between them, like the coordinates
+/// in `p=0,4 v=3,-3`. A `-` or `+` is the sign of the number right after it, if there is one.
+pub fn integers<T: Signed>(input: &str) -> IResult<&str, Vec<T>> {
+    let mut numbers = Vec::new();
+    let mut rest = input;
+    loop {
+        let start = rest
+            .find(|c: char| c == '\n' || c == '-' || c == '+' || c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        rest = &rest[start..];
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (None | Some('\n'), _) => return Ok((rest, numbers)),
+            (Some('-' | '+'), next) if !next.is_some_and(|c| c.is_ascii_digit()) => {
+                rest = &rest[1..];
+            }
+            _ => {
+                let (after, n) = signed(rest)?;
+                numbers.push(n);
+                rest = after;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(unsigned::<u8>("255,"), Ok((",", 255)));
+        assert!(unsigned::<u8>("256").is_err());
+        assert!(unsigned::<u32>("-1").is_err());
+        assert_eq!(unsigned::<usize>("12 3"), Ok((" 3", 12)));
+        assert_eq!(signed::<i32>("-12x"), Ok(("x", -12)));
+        assert_eq!(signed::<i64>("+7"), Ok(("", 7)));
+        let error = common::finish("9999999999", signed::<i32>("9999999999")).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (1, "a number"));
+    }
+
+    #[test]
+    fn finds_every_integer_on_a_line() {
+        let input = "p=0,4 v=3,-3\np=1,1 v=1,1";
+        assert_eq!(
+            integers::<i32>(input),
+            Ok(("\np=1,1 v=1,1", vec![0, 4, 3, -3]))
+        );
+        assert_eq!(
+            integers::<i64>("Button A: X+94, Y-34 - +"),
+            Ok(("", vec![94, -34]))
+        );
+        assert_eq!(integers::<i8>("none"), Ok(("", vec![])));
+        assert!(integers::<i8>("1 1000").is_err());
+    }
+}
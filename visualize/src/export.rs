@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{Frame, Rgb, Simulation};
+
+/// How exported frames are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// A directory of binary PPM images, one per frame.
+    Ppm,
+    /// A directory of PNG images, one per frame.
+    Png,
+    /// A single looping animated GIF.
+    Gif,
+}
+
+impl ImageFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "ppm" => Ok(Self::Ppm),
+            "png" => Ok(Self::Png),
+            "gif" => Ok(Self::Gif),
+            other => Err(format!(
+                "unknown image format `{other}`, expected `ppm`, `png` or `gif`"
+            )),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+            Self::Gif => "gif",
+        }
+    }
+}
+
+/// What [`export`] writes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Export {
+    pub format: ImageFormat,
+    /// The side of a cell, in pixels.
+    pub scale: usize,
+    /// The most frames to write, counting the first; at least one is always written.
+    pub frames: usize,
+    /// The speed of an animation.
+    pub fps: f64,
+}
+
+impl Export {
+    pub fn new(format: ImageFormat) -> Self {
+        Self {
+            format,
+            scale: 4,
+            frames: 1000,
+            fps: 10.0,
+        }
+    }
+}
+
+/// Writes the current frame of `simulation` and the frames of the following steps, until it
+/// finishes or `options.frames` have been written. Image sequences go to `frame-NNNNN` files in
+/// the directory `path`, an animation to the file `path`. Returns the number of frames.
+pub fn export(simulation: &mut dyn Simulation, path: &Path, options: &Export) -> io::Result<usize> {
+    let scale = options.scale.max(1);
+    match options.format {
+        ImageFormat::Ppm | ImageFormat::Png => {
+            fs::create_dir_all(path)?;
+            for_each_frame(simulation, options.frames, |frame, i| {
+                let (width, height, rgb) = pixels(frame, scale);
+                let name = format!("frame-{i:05}.{}", options.format.extension());
+                let out = BufWriter::new(File::create(path.join(name))?);
+                match options.format {
+                    ImageFormat::Ppm => write_ppm(out, width, height, &rgb),
+                    _ => write_png(out, width, height, &rgb),
+                }
+            })
+        }
+        ImageFormat::Gif => {
+            let frame = simulation.frame();
+            let (width, height) = (frame.width() * scale, frame.height() * scale);
+            let too_large = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("a {width}x{height} animation is too large for a GIF"),
+                )
+            };
+            let width = u16::try_from(width).map_err(|_| too_large())?;
+            let height = u16::try_from(height).map_err(|_| too_large())?;
+            let out = BufWriter::new(File::create(path)?);
+            let mut encoder =
+                gif::Encoder::new(out, width, height, &[]).map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+            // GIF delays count hundredths of a second
+            let delay = (100.0 / options.fps)
+                .round()
+                .clamp(1.0, f64::from(u16::MAX)) as u16;
+            for_each_frame(simulation, options.frames, |frame, i| {
+                // every frame of a GIF is drawn over the first one, at its size
+                let size = (frame.width() * scale, frame.height() * scale);
+                if size != (usize::from(width), usize::from(height)) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "frame {i} is {}x{}, but a GIF needs every frame to be {width}x{height}",
+                            size.0, size.1
+                        ),
+                    ));
+                }
+                let mut image = gif_frame(frame, scale, width, height);
+                image.delay = delay;
+                encoder.write_frame(&image).map_err(io::Error::other)
+            })
+        }
+    }
+}
+
+fn for_each_frame(
+    simulation: &mut dyn Simulation,
+    max: usize,
+    mut write: impl FnMut(&Frame, usize) -> io::Result<()>,
+) -> io::Result<usize> {
+    let mut count = 0;
+    loop {
+        write(&simulation.frame(), count)?;
+        count += 1;
+        if count >= max || !simulation.step() {
+            return Ok(count);
+        }
+    }
+}
+
+/// The frame as rows of RGB pixels, every cell a `scale` by `scale` square of its colour, with
+/// the width and height in pixels.
+pub fn pixels(frame: &Frame, scale: usize) -> (usize, usize, Vec<u8>) {
+    let (width, height) = (frame.width() * scale, frame.height() * scale);
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in frame.rows() {
+        for _ in 0..scale {
+            for cell in row {
+                let Rgb(r, g, b) = cell.color;
+                for _ in 0..scale {
+                    rgb.extend([r, g, b]);
+                }
+            }
+        }
+    }
+    (width, height, rgb)
+}
+
+/// Writes a binary (P6) PPM image.
+pub fn write_ppm(mut out: impl Write, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    write!(out, "P6\n{width} {height}\n255\n")?;
+    out.write_all(rgb)?;
+    out.flush()
+}
+
+fn write_png(out: impl Write, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "image too large for a PNG");
+    let width = u32::try_from(width).map_err(too_large)?;
+    let height = u32::try_from(height).map_err(too_large)?;
+    let mut encoder = png::Encoder::new(out, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(rgb).map_err(io::Error::other)
+}
+
+/// A GIF frame with an exact palette when the frame has few enough colours, which they usually
+/// do, and a quantized one otherwise.
+fn gif_frame(frame: &Frame, scale: usize, width: u16, height: u16) -> gif::Frame<'static> {
+    let mut palette = HashMap::new();
+    for cell in frame.cells() {
+        let next = palette.len();
+        palette.entry(cell.color).or_insert(next);
+    }
+    if palette.len() > 256 {
+        let (_, _, rgb) = pixels(frame, scale);
+        return gif::Frame::from_rgb_speed(width, height, &rgb, 10);
+    }
+    let mut colors = vec![0; palette.len() * 3];
+    for (&Rgb(r, g, b), &index) in &palette {
+        colors[index * 3..index * 3 + 3].copy_from_slice(&[r, g, b]);
+    }
+    let mut indices = Vec::with_capacity(usize::from(width) * usize::from(height));
+    for row in frame.rows() {
+        for _ in 0..scale {
+            for cell in row {
+                let index = palette[&cell.color] as u8;
+                indices.extend(std::iter::repeat_n(index, scale));
+            }
+        }
+    }
+    gif::Frame::from_palette_pixels(width, height, indices, colors, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use grid::Grid;
+
+    use super::*;
+    use crate::{tests::Walk, Cell};
+
+    /// A row of cells that grows by one with every step.
+    struct Growing(usize);
+
+    impl Simulation for Growing {
+        fn frame(&self) -> Frame {
+            Grid::filled(self.0, 1, Cell::new('.', Rgb::BACKGROUND))
+        }
+
+        fn step(&mut self) -> bool {
+            self.0 += 1;
+            true
+        }
+
+        fn caption(&self) -> String {
+            format!("{} cells", self.0)
+        }
+    }
+
+    #[test]
+    fn scales_cells_to_pixels() {
+        let frame = Walk { len: 2, pos: 1 }.frame();
+        let (width, height, rgb) = pixels(&frame, 2);
+        assert_eq!((width, height), (4, 2));
+        let Rgb(r, g, b) = Rgb::BACKGROUND;
+        let Rgb(ar, ag, ab) = Rgb::ACTOR;
+        let row = [r, g, b, r, g, b, ar, ag, ab, ar, ag, ab];
+        assert_eq!(rgb, [row, row].concat());
+        let mut ppm = Vec::new();
+        write_ppm(&mut ppm, width, height, &rgb).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + rgb.len());
+    }
+
+    #[test]
+    fn exports_every_format() {
+        let dir = env::temp_dir().join(format!("visualize-export-{}", process::id()));
+        for (format, magic) in [
+            (ImageFormat::Ppm, &b"P6"[..]),
+            (ImageFormat::Png, &b"\x89PNG"[..]),
+        ] {
+            let frames = dir.join(format.extension());
+            let options = Export::new(format);
+            let written = export(&mut Walk { len: 5, pos: 0 }, &frames, &options).unwrap();
+            assert_eq!(written, 5);
+            let last = fs::read(frames.join(format!("frame-00004.{}", format.extension())));
+            assert!(last.unwrap().starts_with(magic));
+        }
+        let gif = dir.join("walk.gif");
+        let options = Export {
+            frames: 3,
+            ..Export::new(ImageFormat::Gif)
+        };
+        assert_eq!(
+            export(&mut Walk { len: 5, pos: 0 }, &gif, &options).unwrap(),
+            3
+        );
+        assert!(fs::read(&gif).unwrap().starts_with(b"GIF89a"));
+        let error = export(&mut Growing(2), &gif, &options).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "frame 1 is 12x4, but a GIF needs every frame to be 8x4"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use common::{ParseError, Solution};
+use grid::Grid;
+
+mod export;
+mod player;
+
+pub use export::{export, pixels, write_ppm, Export, ImageFormat};
+pub use player::{play, Command, Player};
+
+/// A colour, as red, green and blue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BACKGROUND: Self = Self(24, 24, 32);
+    pub const DIM: Self = Self(72, 72, 88);
+    pub const WALL: Self = Self(150, 150, 160);
+    pub const HIGHLIGHT: Self = Self(240, 200, 60);
+    pub const ACTOR: Self = Self(230, 60, 50);
+    pub const CROWD: Self = Self(60, 200, 90);
+}
+
+/// One tile of a frame: a character for the terminal and a colour for images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Rgb,
+}
+
+impl Cell {
+    pub const fn new(glyph: char, color: Rgb) -> Self {
+        Self { glyph, color }
+    }
+}
+
+/// A picture of a simulation at one step.
+pub type Frame = Grid<Cell>;
+
+/// A simulation that is drawn one state at a time.
+pub trait Simulation {
+    /// Draws the current state.
+    fn frame(&self) -> Frame;
+
+    /// Advances by one step, returning `false` without changing anything once it has finished.
+    fn step(&mut self) -> bool;
+
+    /// A short description of the current state, like the step number.
+    fn caption(&self) -> String {
+        String::new()
+    }
+}
+
+/// A day whose solution can be watched step by step.
+pub trait Visualize: Solution {
+    /// The simulation behind `part` of the puzzle, starting from the parsed input.
+    fn simulation(parsed: &Self::Parsed, part: u8) -> Box<dyn Simulation>;
+}
+
+/// Starts the simulation of a part from a raw puzzle input, like [`simulate`].
+pub type Simulator = fn(&str, u8) -> Result<Box<dyn Simulation>, ParseError>;
+
+/// Parses a raw puzzle input and starts the simulation of `part`.
+pub fn simulate<S: Visualize>(input: &str, part: u8) -> Result<Box<dyn Simulation>, ParseError> {
+    S::parse(&common::normalize(input)).map(|parsed| S::simulation(&parsed, part))
+}
+
+/// Advances `simulation` by up to `steps` steps, returning how many it took before finishing.
+pub fn skip(simulation: &mut dyn Simulation, steps: usize) -> usize {
+    (0..steps).take_while(|_| simulation.step()).count()
+}
+
+/// The frame as lines of coloured text for a terminal.
+pub fn ansi(frame: &Frame) -> String {
+    let mut text = String::new();
+    for row in frame.rows() {
+        let mut color = None;
+        for cell in row {
+            if color != Some(cell.color) {
+                let Rgb(r, g, b) = cell.color;
+                write!(text, "\x1b[38;2;{r};{g};{b}m").unwrap();
+                color = Some(cell.color);
+            }
+            text.push(cell.glyph);
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// The frame as plain lines of text.
+pub fn text(frame: &Frame) -> String {
+    frame
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.glyph)
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A dot walking along a strip `len` tiles long.
+    pub(crate) struct Walk {
+        pub(crate) len: usize,
+        pub(crate) pos: usize,
+    }
+
+    impl Simulation for Walk {
+        fn frame(&self) -> Frame {
+            let mut frame = Grid::filled(self.len, 1, Cell::new('.', Rgb::BACKGROUND));
+            frame[(self.pos, 0)] = Cell::new('@', Rgb::ACTOR);
+            frame
+        }
+
+        fn step(&mut self) -> bool {
+            if self.pos + 1 == self.len {
+                return false;
+            }
+            self.pos += 1;
+            true
+        }
+
+        fn caption(&self) -> String {
+            format!("at {}", self.pos)
+        }
+    }
+
+    #[test]
+    fn draws_frames() {
+        let mut walk = Walk { len: 3, pos: 0 };
+        assert_eq!(text(&walk.frame()), "@..\n");
+        assert_eq!(skip(&mut walk, 5), 2);
+        assert_eq!(text(&walk.frame()), "..@\n");
+        assert_eq!(
+            ansi(&walk.frame()),
+            "\x1b[38;2;24;24;32m..\x1b[38;2;230;60;50m@\x1b[0m\n"
+        );
+    }
+}
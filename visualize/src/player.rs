@@ -0,0 +1,189 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{ansi, Simulation};
+
+const MIN_FPS: f64 = 0.25;
+const MAX_FPS: f64 = 240.0;
+
+/// What the viewer asks the player to do, one command per line typed on stdin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    TogglePause,
+    /// Pauses and advances by a single step.
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Command {
+    /// An empty line or `p` pauses and resumes, `n` steps, `+` and `-` change the speed and `q`
+    /// quits.
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "" | "p" => Some(Self::TogglePause),
+            "n" => Some(Self::Step),
+            "+" => Some(Self::Faster),
+            "-" => Some(Self::Slower),
+            "q" => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// The state of the terminal player, apart from the simulation it shows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    pub fps: f64,
+    pub paused: bool,
+    /// Whether the simulation has refused to step any further.
+    pub finished: bool,
+    pub steps: usize,
+}
+
+impl Player {
+    pub fn new(fps: f64) -> Self {
+        Self {
+            fps: fps.clamp(MIN_FPS, MAX_FPS),
+            paused: false,
+            finished: false,
+            steps: 0,
+        }
+    }
+
+    /// Carries out `command`, returning whether the viewer wants to quit.
+    pub fn handle(&mut self, command: Command, simulation: &mut dyn Simulation) -> bool {
+        match command {
+            Command::TogglePause => self.paused = !self.paused,
+            Command::Step => {
+                self.paused = true;
+                self.advance(simulation);
+            }
+            Command::Faster => self.fps = (self.fps * 2.0).min(MAX_FPS),
+            Command::Slower => self.fps = (self.fps / 2.0).max(MIN_FPS),
+            Command::Quit => return true,
+        }
+        false
+    }
+
+    /// Steps the simulation once, noticing when it has finished.
+    pub fn advance(&mut self, simulation: &mut dyn Simulation) {
+        if self.finished {
+            return;
+        }
+        if simulation.step() {
+            self.steps += 1;
+        } else {
+            self.finished = true;
+        }
+    }
+
+    /// How long each frame stays on screen while playing.
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    fn status(&self, caption: &str) -> String {
+        let state = match (self.finished, self.paused) {
+            (true, _) => "finished",
+            (false, true) => "paused",
+            (false, false) => "playing",
+        };
+        format!(
+            "{caption}  step {}  {state} at {} fps\n[Enter] pause/resume  [n] step  [+/-] speed  [q] quit",
+            self.steps, self.fps
+        )
+    }
+}
+
+/// Plays `simulation` on `out` at `fps` frames per second, taking [`Command`]s from stdin. Returns
+/// when the viewer quits, or once the simulation has finished and stdin is closed.
+pub fn play(simulation: &mut dyn Simulation, fps: f64, out: &mut impl Write) -> io::Result<()> {
+    let (send, commands) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(command) = Command::parse(&line) {
+                if send.send(command).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    let mut player = Player::new(fps);
+    loop {
+        let frame = ansi(&simulation.frame());
+        let status = player.status(&simulation.caption());
+        writeln!(out, "\x1b[H\x1b[2J{frame}{status}")?;
+        out.flush()?;
+        let command = if player.paused || player.finished {
+            match commands.recv() {
+                Ok(command) => Some(command),
+                Err(_) => return Ok(()),
+            }
+        } else {
+            match commands.recv_timeout(player.delay()) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    // nobody is typing, so just play to the end
+                    thread::sleep(player.delay());
+                    None
+                }
+            }
+        };
+        match command {
+            Some(command) if player.handle(command, simulation) => return Ok(()),
+            Some(_) => {}
+            None => player.advance(simulation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Walk;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(Command::parse(""), Some(Command::TogglePause));
+        assert_eq!(Command::parse(" n \n"), Some(Command::Step));
+        assert_eq!(Command::parse("q"), Some(Command::Quit));
+        assert_eq!(Command::parse("x"), None);
+    }
+
+    #[test]
+    fn handles_commands() {
+        let mut walk = Walk { len: 3, pos: 0 };
+        let mut player = Player::new(4.0);
+        assert!(!player.handle(Command::Faster, &mut walk));
+        assert_eq!(player.fps, 8.0);
+        assert_eq!(player.delay(), Duration::from_millis(125));
+        player.handle(Command::Slower, &mut walk);
+        player.handle(Command::Slower, &mut walk);
+        assert_eq!(player.fps, 2.0);
+        for _ in 0..20 {
+            player.handle(Command::Slower, &mut walk);
+        }
+        assert_eq!(player.fps, MIN_FPS);
+
+        player.handle(Command::Step, &mut walk);
+        assert!(player.paused);
+        assert_eq!((player.steps, walk.pos), (1, 1));
+        player.handle(Command::TogglePause, &mut walk);
+        assert!(!player.paused);
+        player.advance(&mut walk);
+        player.advance(&mut walk);
+        assert!(player.finished);
+        assert_eq!((player.steps, walk.pos), (2, 2));
+        assert!(player.handle(Command::Quit, &mut walk));
+    }
+}